@@ -1,57 +0,0 @@
-use std::{
-    fmt::Display,
-    ops::{Add, Sub},
-};
-
-use bevy::prelude::Component;
-
-#[cfg(feature = "debug")]
-use bevy::reflect::Reflect;
-#[cfg(feature = "debug")]
-use bevy_inspector_egui::{InspectorOptions, prelude::ReflectInspectorOptions};
-
-#[cfg_attr(feature = "debug", derive(Reflect, InspectorOptions))]
-#[cfg_attr(feature = "debug", reflect(InspectorOptions))]
-#[derive(Debug, Default, Clone, Copy, Ord, PartialEq, PartialOrd, Eq, Hash, Component)]
-pub struct Coordinate {
-    pub x: u16,
-    pub y: u16,
-}
-
-impl Add for Coordinate {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
-    }
-}
-
-impl Sub for Coordinate {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x.saturating_sub(rhs.x),
-            y: self.y.saturating_sub(rhs.y),
-        }
-    }
-}
-
-impl Display for Coordinate {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
-    }
-}
-
-impl Add<(i8, i8)> for Coordinate {
-    type Output = Self;
-
-    fn add(self, (x, y): (i8, i8)) -> Self::Output {
-        let x = ((self.x as i16) + x as i16) as u16;
-        let y = ((self.y as i16) + y as i16) as u16;
-        Self { x, y }
-    }
-}
@@ -5,8 +5,8 @@ use bevy::reflect::Reflect;
 #[cfg(feature = "debug")]
 use bevy_inspector_egui::{InspectorOptions, prelude::ReflectInspectorOptions};
 
-
+/// Highlight and explanation of the current hint
 #[cfg_attr(feature = "debug", derive(Reflect, InspectorOptions))]
 #[cfg_attr(feature = "debug", reflect(InspectorOptions))]
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Eq, Ord, Hash, Component)]
-pub struct Uncover;
+pub struct Hint;
@@ -0,0 +1,15 @@
+use bevy::prelude::Component;
+
+/// Root of the on-screen game information
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct Hud;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct ClockText;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct MineCounterText;
+
+/// Efficiency figures, shown once the board is cleared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct MetricsText;
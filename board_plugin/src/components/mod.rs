@@ -1,9 +1,14 @@
-mod coordinates;
 mod bomb;
 mod bomb_neighbor;
-mod uncover;
+mod hint;
+mod hud;
+mod screens;
 
-pub use coordinates::Coordinate;
 pub use bomb::Bomb;
 pub use bomb_neighbor::BombNeighbor;
-pub use uncover::Uncover;
+pub use hint::Hint;
+pub use hud::{ClockText, Hud, MetricsText, MineCounterText};
+pub use screens::{
+    CustomField, CustomFieldText, HighScorePrompt, HighScoreTable, MenuButton, MenuErrorText,
+    MenuScreen, NameText, StatsScreen,
+};
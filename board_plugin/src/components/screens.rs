@@ -0,0 +1,46 @@
+use bevy::prelude::Component;
+
+/// Root of the statistics screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct StatsScreen;
+
+/// Asks for the player's name after a qualifying win
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct HighScorePrompt;
+
+/// The name typed so far in the high score prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct NameText;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct HighScoreTable;
+
+/// Root of the new game menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct MenuScreen;
+
+/// A field of the custom game form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CustomField {
+    Width,
+    Height,
+    Mines,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub enum MenuButton {
+    Beginner,
+    Intermediate,
+    Expert,
+    Endless,
+    /// Focuses a custom game field for typing
+    Field(CustomField),
+    StartCustom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct CustomFieldText(pub CustomField);
+
+/// Why the last game could not be started from the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+pub struct MenuErrorText;
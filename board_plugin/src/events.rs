@@ -1,14 +1,26 @@
-use crate::components::Coordinate;
 use bevy::prelude::Event;
+use minesweeper_core::{CellChange, Coordinate, Metrics};
 
 #[derive(Debug, Copy, Clone, Event)]
 pub struct TileTriggerEvent(pub Coordinate);
 
 #[derive(Debug, Copy, Clone, Event)]
-pub struct BoardCompletedEvent;
+pub struct BoardCompletedEvent {
+    pub hints_used: u32,
+    pub metrics: Metrics,
+}
 
 #[derive(Debug, Copy, Clone, Event)]
-pub struct BombExplosionEvent;
+pub struct BombExplosionEvent(pub Coordinate);
 
 #[derive(Debug, Copy, Clone, Event)]
 pub struct TileMarkEvent(pub Coordinate);
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TileChordEvent(pub Coordinate);
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct TileChangeEvent(pub CellChange);
+
+#[derive(Debug, Copy, Clone, Event)]
+pub struct HintRequestEvent;
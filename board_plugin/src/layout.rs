@@ -0,0 +1,123 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+use minesweeper_core::{Coordinate, Grid};
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// Where the tiles of a grid sit on the board, relative to its bottom left corner
+#[derive(Debug, Copy, Clone)]
+pub struct Layout {
+    pub grid: Grid,
+    /// Width of a tile, and the distance between the centers of two tiles of a row
+    pub tile_size: f32,
+}
+
+impl Layout {
+    /// Height of a pointy-top hexagon relative to its width
+    const HEX_HEIGHT: f32 = 2. / SQRT_3;
+    /// Distance between two hex rows relative to the tile width
+    const HEX_ROW_HEIGHT: f32 = SQRT_3 / 2.;
+
+    pub fn board_size(&self, width: u16, height: u16) -> Vec2 {
+        let (width, height) = (width as f32, height as f32);
+        match self.grid {
+            Grid::Square => Vec2::new(width, height) * self.tile_size,
+            Grid::Hex => {
+                let shift = if height > 1. { 0.5 } else { 0. };
+                Vec2::new(
+                    width + shift,
+                    (height - 1.) * Self::HEX_ROW_HEIGHT + Self::HEX_HEIGHT,
+                ) * self.tile_size
+            }
+        }
+    }
+
+    /// Size of a tile sprite, `padding` being the gap left between tiles
+    pub fn sprite_size(&self, padding: f32) -> Vec2 {
+        let width = self.tile_size - padding;
+        match self.grid {
+            Grid::Square => Vec2::splat(width),
+            Grid::Hex => Vec2::new(width, width * Self::HEX_HEIGHT),
+        }
+    }
+
+    pub fn tile_center(&self, coordinate: Coordinate) -> Vec2 {
+        let (x, y) = (coordinate.x as f32, coordinate.y as f32);
+        match self.grid {
+            Grid::Square => (Vec2::new(x, y) + 0.5) * self.tile_size,
+            Grid::Hex => {
+                let shift = if coordinate.y % 2 == 1 { 0.5 } else { 0. };
+                Vec2::new(
+                    x + shift + 0.5,
+                    y * Self::HEX_ROW_HEIGHT + Self::HEX_HEIGHT / 2.,
+                ) * self.tile_size
+            }
+        }
+    }
+
+    /// The tile under `position`, which may be outside of the map
+    pub fn coordinate_at(&self, position: Vec2) -> Option<Coordinate> {
+        let (x, y) = match self.grid {
+            Grid::Square => {
+                let position = (position / self.tile_size).floor();
+                (position.x as i32, position.y as i32)
+            }
+            Grid::Hex => self.hex_at(position),
+        };
+        Some(Coordinate {
+            x: u16::try_from(x).ok()?,
+            y: u16::try_from(y).ok()?,
+        })
+    }
+
+    /// Converts to axial hex coordinates around the first tile, rounds to the nearest hex and
+    /// back to the shifted rows of the map
+    fn hex_at(&self, position: Vec2) -> (i32, i32) {
+        let radius = self.tile_size / SQRT_3;
+        let position = position - self.tile_center(Coordinate { x: 0, y: 0 });
+        let q = (SQRT_3 / 3. * position.x - position.y / 3.) / radius;
+        let r = (2. / 3. * position.y) / radius;
+        let s = -q - r;
+        let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+        let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+        if dq > dr && dq > ds {
+            rq = -rr - rs;
+        } else if dr > ds {
+            rr = -rq - rs;
+        }
+        let (q, r) = (rq as i32, rr as i32);
+        (q + (r - (r & 1)) / 2, r)
+    }
+}
+
+/// A white pointy-top hexagon on a transparent background, used to draw untextured tiles of a
+/// hex grid
+pub fn hex_mask(width: u32) -> Image {
+    let height = (width as f32 * Layout::HEX_HEIGHT).round() as u32;
+    let (half_width, half_height) = (width as f32 / 2., height as f32 / 2.);
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let dx = (x as f32 + 0.5 - half_width).abs();
+            let dy = (y as f32 + 0.5 - half_height).abs();
+            let inside =
+                dx <= half_width && dy <= half_height - dx * half_height / (2. * half_width);
+            let alpha = if inside { 255 } else { 0 };
+            data.extend([255, 255, 255, alpha]);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
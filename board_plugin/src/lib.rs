@@ -1,20 +1,33 @@
 use bevy::log;
-use bevy::{ecs::relationship::RelatedSpawnerCommands, platform::collections::HashMap, prelude::*};
-use events::{BoardCompletedEvent, BombExplosionEvent, TileMarkEvent};
+use bevy::{platform::collections::HashMap, prelude::*};
+use events::{
+    BoardCompletedEvent, BombExplosionEvent, HintRequestEvent, TileChangeEvent, TileChordEvent,
+    TileMarkEvent,
+};
 use resources::BoardAssets;
 
-use crate::components::Uncover;
 use crate::{
     bounds::Bounds2,
-    components::{Bomb, BombNeighbor, Coordinate},
     events::TileTriggerEvent,
-    resources::{Board, BoardOptions, BoardPosition, TileSize, tile::Tile, tile_map::TileMap},
+    layout::Layout,
+    resources::{
+        Board, BoardConfig, BoardConfigLoader, BoardOptions, BoardPosition, GameClock, HighScores,
+        ProbabilityOverlay, ReplayFile, ReplayPlayback, SavedGame, Statistics, TileSize,
+    },
+};
+
+pub use minesweeper_core::{
+    Action, CellChange, Coordinate, Game, GameOptions, GameState, GenerationMode, Grid, Replay,
+    Tile, TileMap, Topology,
 };
 
 mod bounds;
 pub mod components;
-mod events;
+pub mod events;
+mod layout;
 pub mod resources;
+pub mod screens;
+pub mod solver;
 mod systems;
 
 pub struct BoardPlugin<T> {
@@ -24,31 +37,74 @@ pub struct BoardPlugin<T> {
 
 impl<T: States> Plugin for BoardPlugin<T> {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(self.running_state.clone()), Self::create_board)
-            .add_systems(
-                Update,
+        app.add_systems(
+            OnEnter(self.running_state.clone()),
+            (Self::create_board, systems::hud::spawn_hud).run_if(resource_exists::<BoardAssets>),
+        )
+        .add_systems(
+            Update,
+            (Self::create_board, systems::hud::spawn_hud)
+                .run_if(in_state(self.running_state.clone()))
+                .run_if(resource_exists::<BoardAssets>)
+                .run_if(not(resource_exists::<Board>)),
+        )
+        .add_systems(
+            Update,
+            (
                 (
-                    systems::input::input_handling,
-                    systems::uncover::trigger_event_handler,
-                    systems::uncover::uncover_tiles,
-                    systems::mark::mark_tiles,
+                    systems::endless::pan_camera,
+                    systems::endless::stream_chunks,
                 )
-                    .run_if(in_state(self.running_state.clone())),
+                    .run_if(systems::endless::is_endless),
+                systems::input::input_handling.run_if(not(resource_exists::<ReplayPlayback>)),
+                systems::replay::play_replay.run_if(resource_exists::<ReplayPlayback>),
+                systems::replay::record_actions,
+                systems::uncover::trigger_event_handler,
+                systems::uncover::chord_event_handler,
+                systems::mark::mark_tiles,
+                systems::replay::finish_replay,
+                systems::statistics::record_statistics,
+                systems::high_scores::check_high_score,
+                systems::high_scores::name_entry_input,
+                systems::render::apply_tile_changes,
+                systems::overlay::update_probability_overlay,
+                systems::hint::clear_hints,
+                systems::hint::hint_handler,
+                systems::hud::tick_clock,
+                systems::hud::update_hud,
+                systems::hud::update_metrics_text,
             )
-            .add_systems(
-                OnExit(self.running_state.clone()),
-                Self::cleanup_board.run_if(not(in_state(self.paused_state.clone()))),
+                .chain()
+                .run_if(in_state(self.running_state.clone()))
+                .run_if(resource_exists::<Board>),
+        )
+        .add_systems(Update, systems::config::apply_board_config)
+        .add_systems(
+            OnExit(self.running_state.clone()),
+            (
+                Self::cleanup_board,
+                systems::hud::despawn_hud,
+                systems::high_scores::despawn_high_scores,
             )
-            .add_event::<TileTriggerEvent>()
-            .add_event::<TileMarkEvent>()
-            .add_event::<BombExplosionEvent>()
-            .add_event::<BoardCompletedEvent>();
+                .run_if(not(in_state(self.paused_state.clone()))),
+        )
+        .init_asset::<BoardConfig>()
+        .init_asset_loader::<BoardConfigLoader>()
+        .init_resource::<ProbabilityOverlay>()
+        .init_resource::<Statistics>()
+        .init_resource::<HighScores>()
+        .add_event::<TileTriggerEvent>()
+        .add_event::<TileMarkEvent>()
+        .add_event::<TileChordEvent>()
+        .add_event::<TileChangeEvent>()
+        .add_event::<HintRequestEvent>()
+        .add_event::<BombExplosionEvent>()
+        .add_event::<BoardCompletedEvent>();
         #[cfg(feature = "debug")]
         {
-            app.register_type::<crate::components::Coordinate>();
             app.register_type::<crate::components::BombNeighbor>();
             app.register_type::<crate::components::Bomb>();
-            app.register_type::<crate::components::Uncover>();
+            app.register_type::<crate::components::Hint>();
         }
         log::info!("Loaded Board Plugin");
     }
@@ -61,40 +117,88 @@ impl<T> BoardPlugin<T> {
         board_assets: Res<BoardAssets>,
         window: Query<&Window>,
         board: Option<Res<Board>>,
+        (replay_file, saved_game): (Option<Res<ReplayFile>>, Option<Res<SavedGame>>),
+        mut tile_change_ewr: EventWriter<TileChangeEvent>,
     ) {
         if board.is_some() {
             return;
         }
-        let options = match board_options {
-            None => BoardOptions::default(),
-            Some(o) => o.clone(),
+        commands.remove_resource::<ReplayPlayback>();
+        let saved = match (replay_file, saved_game) {
+            (Some(file), _) => {
+                log::info!("Playing back replay");
+                commands.remove_resource::<ReplayFile>();
+                commands.insert_resource(ReplayPlayback::new(file.replay.clone()));
+                SavedGame::new(file.options.clone(), file.replay.game())
+            }
+            (None, Some(saved)) => {
+                log::info!("Restoring saved game");
+                commands.remove_resource::<SavedGame>();
+                saved.clone()
+            }
+            (None, None) => {
+                let mut options = match board_options {
+                    None => BoardOptions::default(),
+                    Some(o) => o.clone(),
+                };
+                if let Err(e) = options.validate() {
+                    log::error!("Invalid board options, using the defaults: {}", e);
+                    options = BoardOptions::default();
+                    commands.insert_resource(options.clone());
+                }
+                let game = options.new_game();
+                SavedGame::new(options, game)
+            }
         };
-        let mut tile_map = TileMap::empty(options.map_size.0, options.map_size.1);
-        tile_map.set_bombs(options.bomb_count);
-        #[cfg(feature = "debug")]
-        log::info!("{}", tile_map.console_output());
+        let SavedGame {
+            version: _,
+            options,
+            game,
+            elapsed,
+            hints_used,
+            replay,
+            clicks,
+            effective_clicks,
+        } = saved;
+        let replay = replay.unwrap_or_else(|| Replay::new(&game));
+        let three_bv = if game.is_generated() {
+            game.tile_map().three_bv()
+        } else {
+            0
+        };
+        log::info!("board seed: {}", game.seed());
+        let tile_map = game.tile_map();
+        let endless = tile_map.is_endless();
         let tile_size = match options.tile_size {
             TileSize::Fixed(v) => v,
-            TileSize::Adaptive { min, max } => Self::adaptative_tile_size(
-                window,
-                (min, max),
-                (tile_map.width(), tile_map.height()),
-            ),
+            TileSize::Adaptive { max, .. } if endless => max,
+            TileSize::Adaptive { min, max } => {
+                Self::adaptative_tile_size(window, (min, max), tile_map)
+            }
+        };
+        let layout = Layout {
+            grid: tile_map.grid(),
+            tile_size,
         };
-        let board_size = Vec2::new(
-            tile_map.width() as f32 * tile_size,
-            tile_map.height() as f32 * tile_size,
-        );
+        let board_size = layout.board_size(tile_map.width(), tile_map.height());
         log::info!("board size: {}", board_size);
         let board_position = match options.position {
+            // The view starts on the middle tile of an endless board, whose chunks are
+            // spawned around the camera
+            _ if endless => {
+                let middle = Coordinate {
+                    x: tile_map.width() / 2,
+                    y: tile_map.height() / 2,
+                };
+                -layout.tile_center(middle).extend(0.)
+            }
             BoardPosition::Centered { offset } => {
                 Vec3::new(-(board_size.x / 2.), -(board_size.y / 2.), 0.) + offset
             }
             BoardPosition::Custom(p) => p,
         };
-        let mut covered_tiles =
-            HashMap::with_capacity((tile_map.width() * tile_map.height()).into());
-        let mut safe_start = None;
+        let mut tiles = HashMap::new();
+        let mut covered_tiles = HashMap::new();
         let board_entity = commands
             .spawn((
                 Name::new("Board"),
@@ -103,6 +207,9 @@ impl<T> BoardPlugin<T> {
                 Visibility::default(),
             ))
             .with_children(|parent| {
+                if endless {
+                    return;
+                }
                 parent.spawn((
                     Sprite {
                         color: board_assets.board_material.color,
@@ -113,146 +220,76 @@ impl<T> BoardPlugin<T> {
                     Transform::from_xyz(board_size.x / 2., board_size.y / 2., 0.),
                     Name::new("Background"),
                 ));
-                Self::spawn_tiles(
+                let coordinates = (0..tile_map.height())
+                    .flat_map(|y| (0..tile_map.width()).map(move |x| Coordinate { x, y }));
+                systems::render::spawn_tiles(
                     parent,
-                    &tile_map,
-                    tile_size,
+                    coordinates,
+                    layout,
                     options.tile_padding,
                     &board_assets,
+                    &mut tiles,
                     &mut covered_tiles,
-                    &mut safe_start,
                 );
             })
             .id();
-        if options.safe_start {
-            if let Some(entity) = safe_start {
-                commands.entity(entity).insert(Uncover);
+        for &coordinate in tiles.keys() {
+            for change in game.restored_changes(coordinate) {
+                tile_change_ewr.write(TileChangeEvent(change));
             }
         }
         commands.insert_resource(Board {
-            tile_map,
+            game,
             bounds: Bounds2 {
                 position: board_position.xy(),
                 size: board_size,
             },
             tile_size,
+            tiles,
             covered_tiles,
+            chunks: HashMap::new(),
             entity: board_entity,
-            marked_tiles: Vec::new(),
+            options,
+            hints_used,
+            replay,
+            three_bv,
+            clicks,
+            effective_clicks,
         });
+        commands.insert_resource(GameClock::with_elapsed(elapsed));
     }
 
     fn adaptative_tile_size(
         window: Query<&Window>,
-        (min, max): (f32, f32),      // Tile size constraints
-        (width, height): (u16, u16), // Tile map dimensions
+        (min, max): (f32, f32), // Tile size constraints
+        tile_map: &TileMap,
     ) -> f32 {
         let window = &window.single().unwrap().resolution;
-        let max_width = window.width() / width as f32;
-        let max_heigth = window.height() / height as f32;
+        // Size of the board with tiles one unit wide
+        let unit_size = Layout {
+            grid: tile_map.grid(),
+            tile_size: 1.,
+        }
+        .board_size(tile_map.width(), tile_map.height());
+        let max_width = window.width() / unit_size.x;
+        let max_heigth = window.height() / unit_size.y;
         max_width.min(max_heigth).clamp(min, max)
     }
 
-    fn bomb_count_text_bundle(
-        count: u8,
-        board_assets: &BoardAssets,
-        font_size: f32,
-    ) -> (Text2d, TextFont, TextColor, Transform) {
-        let color = board_assets.bomb_counter_color(count);
-        (
-            Text2d::new(count.to_string()),
-            TextFont {
-                font: board_assets.bomb_counter_font.clone(),
-                font_size,
-                ..Default::default()
-            },
-            TextColor(color),
-            Transform::from_xyz(0., 0., 1.),
-        )
-    }
-
-    fn spawn_tiles(
-        parent: &mut RelatedSpawnerCommands<'_, ChildOf>,
-        tile_map: &TileMap,
-        size: f32,
-        padding: f32,
-        board_assets: &BoardAssets,
-        covered_tiles: &mut HashMap<Coordinate, Entity>,
-        safe_start_entity: &mut Option<Entity>,
+    fn cleanup_board(
+        board: Option<Res<Board>>,
+        mut commands: Commands,
+        mut camera: Query<&mut Transform, With<Camera2d>>,
     ) {
-        for (y, line) in tile_map.iter().enumerate() {
-            for (x, tile_type) in line.iter().enumerate() {
-                let coordinate = Coordinate {
-                    x: x as u16,
-                    y: y as u16,
-                };
-                let mut tile = parent.spawn((
-                    Sprite {
-                        color: board_assets.tile_material.color,
-                        custom_size: Some(Vec2::splat(size - padding)),
-                        image: board_assets.tile_material.texture.clone(),
-                        ..Default::default()
-                    },
-                    Transform::from_xyz(
-                        (x as f32 * size) + (size / 2.),
-                        (y as f32 * size) + (size / 2.),
-                        1.,
-                    ),
-                    Name::new(format!("Tile ({}, {})", x, y)),
-                    coordinate,
-                    Visibility::default(),
-                ));
-                tile.with_children(|p| {
-                    let entity = p
-                        .spawn((
-                            Sprite {
-                                custom_size: Some(Vec2::splat(size - padding)),
-                                color: board_assets.covered_tile_material.color,
-                                image: board_assets.covered_tile_material.texture.clone(),
-                                ..Default::default()
-                            },
-                            Transform::from_xyz(0., 0., 2.),
-                            Name::new("Tile Cover"),
-                        ))
-                        .id();
-                    covered_tiles.insert(coordinate, entity);
-                    if safe_start_entity.is_none() && *tile_type == Tile::Empty {
-                        *safe_start_entity = Some(entity)
-                    }
-                });
-                match tile_type {
-                    Tile::Bomb => {
-                        tile.insert(Bomb);
-                        tile.with_children(|parent| {
-                            parent.spawn((
-                                Sprite {
-                                    custom_size: Some(Vec2::splat(size - padding)),
-                                    color: board_assets.bomb_material.color,
-                                    image: board_assets.bomb_material.texture.clone(),
-                                    ..Default::default()
-                                },
-                                Transform::from_xyz(0., 0., 1.),
-                            ));
-                        });
-                    }
-                    Tile::BombNeighbor(v) => {
-                        tile.insert(BombNeighbor { count: *v });
-                        tile.with_children(|parent| {
-                            parent.spawn(Self::bomb_count_text_bundle(
-                                *v,
-                                board_assets,
-                                (size - padding) / 2.,
-                            ));
-                        });
-                    }
-                    Tile::Empty => (),
-                }
-            }
-        }
-    }
-
-    fn cleanup_board(board: Res<Board>, mut commands: Commands) {
+        let Some(board) = board else {
+            return;
+        };
         commands.entity(board.entity).despawn();
         commands.remove_resource::<Board>();
+        // Endless boards pan the camera
+        for mut transform in camera.iter_mut() {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+        }
     }
 }
@@ -1,79 +1,68 @@
 use crate::bounds::Bounds2;
-use crate::{Coordinate, TileMap};
+use crate::layout::Layout;
+use crate::resources::BoardOptions;
 use bevy::platform::collections::HashMap;
-use bevy::{log, prelude::*};
+use bevy::prelude::*;
+use minesweeper_core::{ChunkCoordinate, ClickCounts, Coordinate, Game, Metrics, Replay};
+use std::time::Duration;
 
 #[derive(Debug, Resource)]
 pub struct Board {
-    pub tile_map: TileMap,
+    pub game: Game,
     pub bounds: Bounds2,
     pub tile_size: f32,
+    pub tiles: HashMap<Coordinate, Entity>,
     pub covered_tiles: HashMap<Coordinate, Entity>,
+    /// Chunks of an endless board whose tiles are spawned, only the ones near the view
+    pub chunks: HashMap<ChunkCoordinate, Entity>,
     pub entity: Entity,
-    pub marked_tiles: Vec<Coordinate>,
+    pub options: BoardOptions,
+    pub hints_used: u32,
+    /// Every action taken on the board so far
+    pub replay: Replay,
+    /// 3BV of the layout, zero until the mines are placed
+    pub three_bv: u32,
+    pub clicks: ClickCounts,
+    /// Clicks that changed the board
+    pub effective_clicks: u32,
 }
 
 impl Board {
-    pub fn mouse_position(&self, window: &Window, position: Vec2) -> Option<Coordinate> {
-        let window_size = Vec2::new(window.width(), window.height());
-        let position = position - window_size / 2.;
+    /// The tile under a point of the world
+    pub fn mouse_position(&self, position: Vec2) -> Option<Coordinate> {
         if !self.bounds.in_bounds(position) {
             return None;
         }
-        let coordinate = position - self.bounds.position;
-        Some(Coordinate {
-            x: (coordinate.x / self.tile_size) as u16,
-            y: (coordinate.y / self.tile_size) as u16,
-        })
+        let coordinate = self
+            .layout()
+            .coordinate_at(position - self.bounds.position)?;
+        self.game
+            .tile_map()
+            .contains(coordinate)
+            .then_some(coordinate)
     }
 
-    pub fn tile_to_uncover(&self, coord: &Coordinate) -> Option<&Entity> {
-        if self.marked_tiles.contains(coord) {
-            None
-        } else {
-            self.covered_tiles.get(coord)
+    pub(crate) fn layout(&self) -> Layout {
+        Layout {
+            grid: self.game.tile_map().grid(),
+            tile_size: self.tile_size,
         }
     }
 
-    pub fn try_uncover_tile(&mut self, coord: &Coordinate) -> Option<Entity> {
-        if self.marked_tiles.contains(coord) {
-            self.unmark_tile(coord)?;
-        }
-        self.covered_tiles.remove(coord)
-    }
-
-    pub fn adjacent_covered_tiles(&self, coord: Coordinate) -> Vec<Entity> {
-        self.tile_map
-            .safe_square_at(coord)
-            .filter_map(|c| self.covered_tiles.get(&c))
-            .copied()
-            .collect()
+    pub fn seed(&self) -> u64 {
+        self.game.seed()
     }
 
-    fn unmark_tile(&mut self, coord: &Coordinate) -> Option<Coordinate> {
-        let pos = match self.marked_tiles.iter().position(|a| a == coord) {
-            None => {
-                log::error!("Failed to unmark tile at {}", coord);
-                return None;
-            }
-            Some(p) => p,
-        };
-        Some(self.marked_tiles.remove(pos))
-    }
-
-    pub fn is_completed(&self) -> bool {
-        self.tile_map.bomb_count() as usize == self.covered_tiles.len()
+    pub fn metrics(&self, time: Duration) -> Metrics {
+        Metrics {
+            three_bv: self.three_bv,
+            time,
+            clicks: self.clicks,
+            effective_clicks: self.effective_clicks,
+        }
     }
 
-    pub fn try_toggle_mark(&mut self, coord: &Coordinate) -> Option<(Entity, bool)> {
-        let entity = *self.covered_tiles.get(coord)?;
-        let mark = if self.marked_tiles.contains(coord) {
-            self.unmark_tile(coord)?;
-            false
-        }else{
-            self.marked_tiles.push(*coord);
-            true
-        };
-        Some((entity, mark))
+    pub fn uncover_tile(&mut self, coord: &Coordinate) -> Option<Entity> {
+        self.covered_tiles.remove(coord)
     }
 }
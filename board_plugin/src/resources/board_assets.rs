@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use minesweeper_core::Grid;
 
 #[derive(Debug, Clone)]
 pub struct SpriteMaterial {
@@ -25,6 +26,11 @@ pub struct BoardAssets {
     pub bomb_counter_colors: Vec<Color>,
     pub flag_material: SpriteMaterial,
     pub bomb_material: SpriteMaterial,
+    pub exploded_tile_material: SpriteMaterial,
+    pub wrong_flag_material: SpriteMaterial,
+    pub hint_material: SpriteMaterial,
+    /// Shape given to untextured tiles on a hex grid
+    pub hex_mask: Handle<Image>,
 }
 
 impl BoardAssets {
@@ -35,9 +41,25 @@ impl BoardAssets {
             Color::linear_rgb(1., 1., 0.),
             Color::linear_rgb(1., 0.65, 0.),
             Color::linear_rgb(0.5, 0., 0.5),
+            Color::linear_rgb(1., 0., 0.),
+            Color::linear_rgb(0., 1., 1.),
+            Color::linear_rgb(0.5, 0.5, 0.5),
+            Color::linear_rgb(0.3, 0.5, 1.),
+            Color::linear_rgb(1., 0.3, 0.8),
+            Color::linear_rgb(0.6, 0.35, 0.1),
+            Color::linear_rgb(0.6, 0., 0.),
         ]
     }
 
+    /// Image to draw `material` with on `grid`, untextured materials are cut to the tile shape
+    pub fn image(&self, material: &SpriteMaterial, grid: Grid) -> Handle<Image> {
+        if grid == Grid::Hex && material.texture == Handle::default() {
+            return self.hex_mask.clone();
+        }
+        material.texture.clone()
+    }
+
+    /// Color of a number, counts past the end of `bomb_counter_colors` share its last color
     pub fn bomb_counter_color(&self, counter: u8) -> Color {
         let counter = counter.saturating_sub(1) as usize;
         match self.bomb_counter_colors.get(counter) {
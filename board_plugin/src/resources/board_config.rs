@@ -0,0 +1,154 @@
+use std::{error::Error, fmt::Display, io};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    layout,
+    resources::{BoardAssets, BoardOptions, SpriteMaterial},
+};
+
+/// Width in pixels of the generated hex tile mask
+const HEX_MASK_WIDTH: u32 = 64;
+
+/// Board options and assets loaded from a `.board.ron` file
+#[derive(Debug, Clone, Asset, TypePath)]
+pub struct BoardConfig {
+    pub options: BoardOptions,
+    pub assets: BoardAssets,
+}
+
+/// The config the plugin builds boards from, hot-reloading it when the file changes
+#[derive(Debug, Clone, Resource)]
+pub struct BoardConfigHandle(pub Handle<BoardConfig>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpriteMaterialDescriptor {
+    pub color: Color,
+    pub texture: Option<String>,
+}
+
+/// Serializable form of `BoardAssets`, with asset paths instead of handles
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardAssetsDescriptor {
+    pub label: String,
+    pub board_material: SpriteMaterialDescriptor,
+    pub tile_material: SpriteMaterialDescriptor,
+    pub covered_tile_material: SpriteMaterialDescriptor,
+    pub bomb_counter_font: String,
+    pub bomb_counter_colors: Vec<Color>,
+    pub flag_material: SpriteMaterialDescriptor,
+    pub bomb_material: SpriteMaterialDescriptor,
+    pub exploded_tile_material: SpriteMaterialDescriptor,
+    pub wrong_flag_material: SpriteMaterialDescriptor,
+    pub hint_material: SpriteMaterialDescriptor,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardConfigDescriptor {
+    #[serde(default)]
+    pub options: BoardOptions,
+    pub assets: BoardAssetsDescriptor,
+}
+
+#[derive(Debug, Default)]
+pub struct BoardConfigLoader;
+
+#[derive(Debug)]
+pub enum BoardConfigError {
+    Io(io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl Default for SpriteMaterialDescriptor {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            texture: None,
+        }
+    }
+}
+
+impl SpriteMaterialDescriptor {
+    fn load(&self, load_context: &mut LoadContext) -> SpriteMaterial {
+        SpriteMaterial {
+            color: self.color,
+            texture: match &self.texture {
+                Some(path) => load_context.load(path),
+                None => Handle::default(),
+            },
+        }
+    }
+}
+
+impl BoardAssetsDescriptor {
+    fn load(&self, load_context: &mut LoadContext) -> BoardAssets {
+        BoardAssets {
+            label: self.label.clone(),
+            board_material: self.board_material.load(load_context),
+            tile_material: self.tile_material.load(load_context),
+            covered_tile_material: self.covered_tile_material.load(load_context),
+            bomb_counter_font: load_context.load(&self.bomb_counter_font),
+            bomb_counter_colors: self.bomb_counter_colors.clone(),
+            flag_material: self.flag_material.load(load_context),
+            bomb_material: self.bomb_material.load(load_context),
+            exploded_tile_material: self.exploded_tile_material.load(load_context),
+            wrong_flag_material: self.wrong_flag_material.load(load_context),
+            hint_material: self.hint_material.load(load_context),
+            hex_mask: load_context
+                .add_labeled_asset("hex_mask".to_string(), layout::hex_mask(HEX_MASK_WIDTH)),
+        }
+    }
+}
+
+impl AssetLoader for BoardConfigLoader {
+    type Asset = BoardConfig;
+    type Settings = ();
+    type Error = BoardConfigError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let descriptor: BoardConfigDescriptor = ron::de::from_bytes(&bytes)?;
+        Ok(BoardConfig {
+            options: descriptor.options,
+            assets: descriptor.assets.load(load_context),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["board.ron"]
+    }
+}
+
+impl Display for BoardConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read board config: {}", e),
+            Self::Ron(e) => write!(f, "invalid board config: {}", e),
+        }
+    }
+}
+
+impl Error for BoardConfigError {}
+
+impl From<io::Error> for BoardConfigError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for BoardConfigError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Ron(value)
+    }
+}
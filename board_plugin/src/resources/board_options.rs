@@ -1,4 +1,10 @@
-use bevy::prelude::*;
+use std::{error::Error, fmt::Display, ops::RangeInclusive, path::Path};
+
+use bevy::{log, prelude::*};
+use minesweeper_core::{
+    ChunkCoordinate, Coordinate, Game, GameOptions, GenerationMode, Grid, NeighborhoodRule, Tile,
+    TileMap, Topology,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,16 +19,280 @@ pub enum BoardPosition {
     Custom(Vec3),
 }
 
+/// Largest board, in tiles, that `BoardOptions::validate` accepts
+pub const MAX_TILES: u32 = 250_000;
+
+/// Highest share of mined tiles `BoardOptions::validate` accepts for no guess generation,
+/// denser boards are rarely solvable and stall the first reveal
+pub const MAX_NO_GUESS_DENSITY: f64 = 0.22;
+
+/// Mines per tile `BoardOptions::validate` accepts on endless boards, sparser ones would let
+/// an opening run on for ever
+pub const ENDLESS_DENSITY: RangeInclusive<f64> = 0.15..=0.5;
+
 #[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+#[serde(default)]
 pub struct BoardOptions {
     pub map_size: (u16, u16),
     pub bomb_count: u16,
     pub position: BoardPosition,
     pub tile_size: TileSize,
     pub tile_padding: f32,
+    /// Mines are placed on the first reveal, which is always safe. With a safe start its
+    /// neighbours are kept clear too, so the first click opens an area
     pub safe_start: bool,
+    pub seed: Option<u64>,
+    pub generation: GenerationMode,
+    /// With `Toroidal`, neighbours wrap around the edges so every tile has as many of them
+    pub topology: Topology,
+    pub grid: Grid,
+    /// Which tiles a number counts mines in on a square grid, hex grids always use the 6
+    /// adjacent tiles
+    pub neighborhood: NeighborhoodRule,
+    /// Most mines a tile can hold, up to `Tile::MAX_MINES`. Numbers sum the mines around them
+    /// and right clicks cycle the flag count up to it
+    pub max_mines_per_tile: u8,
+    /// Fixed layout to play instead of a generated one, either in the text board format or
+    /// as an `.mbf` file. Its size and mines take over `map_size` and `bomb_count`
+    pub board_file: Option<String>,
+    /// Plays an endless board with this many mines per tile on average instead of a finite
+    /// one. `map_size`, `bomb_count`, `topology` and `generation` do not apply to it
+    pub endless_density: Option<f64>,
+}
+
+/// Why `BoardOptions` cannot make a playable board
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardOptionsError {
+    ZeroSize,
+    TooLarge {
+        tiles: u32,
+    },
+    /// At least one tile must stay free for the first reveal
+    TooManyMines {
+        mines: u16,
+        max: u32,
+    },
+    /// Not enough free tiles around every possible first reveal
+    SafeStartImpossible {
+        mines: u16,
+        max: u32,
+    },
+    /// Hex rows alternate their shift, so they only wrap around onto a row of the same parity
+    OddToroidalHexHeight,
+    MinesPerTile(u8),
+    /// The solver behind no guess generation only handles one mine per tile
+    MultiMineNoGuess,
+    /// Too many mines for no guess generation to find a solvable layout
+    NoGuessTooDense {
+        mines: u16,
+        max: u32,
+    },
+    /// Endless mine density outside of the supported range
+    EndlessDensity,
+}
+
+impl BoardOptions {
+    /// 9x9 with 10 mines
+    pub fn beginner() -> Self {
+        Self {
+            map_size: (9, 9),
+            bomb_count: 10,
+            ..Default::default()
+        }
+    }
+
+    /// 16x16 with 40 mines
+    pub fn intermediate() -> Self {
+        Self {
+            map_size: (16, 16),
+            bomb_count: 40,
+            ..Default::default()
+        }
+    }
+
+    /// 30x16 with 99 mines
+    pub fn expert() -> Self {
+        Self {
+            map_size: (30, 16),
+            bomb_count: 99,
+            ..Default::default()
+        }
+    }
+
+    /// Endless board with a mine on a fifth of the tiles
+    pub fn endless() -> Self {
+        Self {
+            endless_density: Some(0.2),
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), BoardOptionsError> {
+        if !(1..=Tile::MAX_MINES).contains(&self.max_mines_per_tile) {
+            return Err(BoardOptionsError::MinesPerTile(self.max_mines_per_tile));
+        }
+        if let Some(density) = self.endless_density {
+            return match ENDLESS_DENSITY.contains(&density) {
+                true => Ok(()),
+                false => Err(BoardOptionsError::EndlessDensity),
+            };
+        }
+        let (width, height) = (self.map_size.0 as u32, self.map_size.1 as u32);
+        let tiles = width * height;
+        if tiles == 0 {
+            return Err(BoardOptionsError::ZeroSize);
+        }
+        if tiles > MAX_TILES {
+            return Err(BoardOptionsError::TooLarge { tiles });
+        }
+        let per_tile = self.max_mines_per_tile as u32;
+        if per_tile > 1 && self.generation == GenerationMode::NoGuess {
+            return Err(BoardOptionsError::MultiMineNoGuess);
+        }
+        let mines = self.bomb_count;
+        if mines as u32 > (tiles - 1) * per_tile {
+            return Err(BoardOptionsError::TooManyMines {
+                mines,
+                max: (tiles - 1) * per_tile,
+            });
+        }
+        let no_guess_max = (tiles as f64 * MAX_NO_GUESS_DENSITY) as u32;
+        if self.generation == GenerationMode::NoGuess && mines as u32 > no_guess_max {
+            return Err(BoardOptionsError::NoGuessTooDense {
+                mines,
+                max: no_guess_max,
+            });
+        }
+        if self.grid == Grid::Hex && self.topology == Topology::Toroidal && height % 2 == 1 {
+            return Err(BoardOptionsError::OddToroidalHexHeight);
+        }
+        // The first reveal and its neighbours, wherever they are the most
+        let safe_area = 1 + self.max_neighbors();
+        let safe_start_max = tiles.saturating_sub(safe_area) * per_tile;
+        if self.safe_start && mines as u32 > safe_start_max {
+            return Err(BoardOptionsError::SafeStartImpossible {
+                mines,
+                max: safe_start_max,
+            });
+        }
+        Ok(())
+    }
+
+    /// Largest number of neighbours of a tile of the board
+    fn max_neighbors(&self) -> u32 {
+        let (width, height) = self.map_size;
+        let neighborhood = self.neighborhood.on(self.grid);
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinate { x, y }))
+            .map(|c| {
+                self.topology
+                    .neighbors(c, neighborhood.offsets(c), width, height)
+                    .count() as u32
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn game_options(&self) -> GameOptions {
+        let options = GameOptions {
+            width: self.map_size.0,
+            height: self.map_size.1,
+            bomb_count: self.bomb_count,
+            safe_start: self.safe_start,
+            seed: self.seed,
+            generation: self.generation,
+            topology: self.topology,
+            grid: self.grid,
+            neighborhood: self.neighborhood,
+            max_mines_per_tile: self.max_mines_per_tile,
+            endless: false,
+        };
+        match self.endless_density {
+            Some(density) => {
+                let chunk_tiles = ChunkCoordinate::SIZE as f64 * ChunkCoordinate::SIZE as f64;
+                GameOptions {
+                    width: TileMap::ENDLESS_SIZE,
+                    height: TileMap::ENDLESS_SIZE,
+                    bomb_count: (density * chunk_tiles).round() as u16,
+                    generation: GenerationMode::Random,
+                    topology: Topology::Toroidal,
+                    endless: true,
+                    ..options
+                }
+            }
+            None => options,
+        }
+    }
+
+    /// Starts a game from `board_file` when set, falling back to a generated board if it
+    /// cannot be read
+    pub fn new_game(&self) -> Game {
+        if let Some(path) = &self.board_file {
+            match Self::read_board_file(path.as_ref()) {
+                Ok(game) => return game,
+                Err(e) => log::error!("Failed to load board file {}: {}", path, e),
+            }
+        }
+        Game::new(self.game_options())
+    }
+
+    fn read_board_file(path: &Path) -> Result<Game, Box<dyn Error>> {
+        let bytes = std::fs::read(path)?;
+        if path.extension().is_some_and(|ext| ext == "mbf") {
+            return Ok(Game::from_tile_map(TileMap::from_mbf(&bytes)?));
+        }
+        Ok(std::str::from_utf8(&bytes)?.parse()?)
+    }
+}
+
+impl Display for BoardOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroSize => write!(f, "board has no tiles"),
+            Self::TooLarge { tiles } => {
+                write!(
+                    f,
+                    "board has {} tiles, at most {} are supported",
+                    tiles, MAX_TILES
+                )
+            }
+            Self::TooManyMines { mines, max } => {
+                write!(f, "{} mines do not fit, at most {} do", mines, max)
+            }
+            Self::SafeStartImpossible { mines, max } => write!(
+                f,
+                "{} mines leave no room for a safe start, at most {} do",
+                mines, max
+            ),
+            Self::OddToroidalHexHeight => {
+                write!(f, "a toroidal hex board needs an even number of rows")
+            }
+            Self::MinesPerTile(n) => write!(
+                f,
+                "tiles hold {} mines, between 1 and {} are supported",
+                n,
+                Tile::MAX_MINES
+            ),
+            Self::MultiMineNoGuess => {
+                write!(f, "no guess boards only support one mine per tile")
+            }
+            Self::NoGuessTooDense { mines, max } => write!(
+                f,
+                "{} mines are too many for a no guess board, at most {} are",
+                mines, max
+            ),
+            Self::EndlessDensity => write!(
+                f,
+                "endless mine density must be between {} and {}",
+                ENDLESS_DENSITY.start(),
+                ENDLESS_DENSITY.end()
+            ),
+        }
+    }
 }
 
+impl Error for BoardOptionsError {}
+
 impl Default for TileSize {
     fn default() -> Self {
         Self::Adaptive { min: 10., max: 50. }
@@ -46,6 +316,14 @@ impl Default for BoardOptions {
             tile_size: Default::default(),
             tile_padding: 0.,
             safe_start: false,
+            seed: None,
+            generation: GenerationMode::Random,
+            topology: Topology::Bounded,
+            grid: Grid::Square,
+            neighborhood: NeighborhoodRule::Moore,
+            max_mines_per_tile: 1,
+            board_file: None,
+            endless_density: None,
         }
     }
 }
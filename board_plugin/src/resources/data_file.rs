@@ -0,0 +1,65 @@
+use std::{error::Error, fmt::Display, fs, io, path::Path};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Failure to read or write one of the RON files the game keeps between runs
+#[derive(Debug)]
+pub enum DataFileError {
+    Io(io::Error),
+    Serialize(ron::Error),
+    Deserialize(ron::error::SpannedError),
+    /// A file written in another format, which cannot be read back
+    Outdated {
+        version: u32,
+        expected: u32,
+    },
+}
+
+pub(crate) fn write_ron(
+    value: &impl Serialize,
+    path: impl AsRef<Path>,
+) -> Result<(), DataFileError> {
+    let data = ron::ser::to_string_pretty(value, Default::default())?;
+    fs::write(path, data)?;
+    Ok(())
+}
+
+pub(crate) fn read_ron<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<T, DataFileError> {
+    let data = fs::read_to_string(path)?;
+    Ok(ron::from_str(&data)?)
+}
+
+impl Display for DataFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "file access failed: {}", e),
+            Self::Serialize(e) => write!(f, "failed to serialize: {}", e),
+            Self::Deserialize(e) => write!(f, "invalid file: {}", e),
+            Self::Outdated { version, expected } => write!(
+                f,
+                "file version {} is not supported, expected {}",
+                version, expected
+            ),
+        }
+    }
+}
+
+impl Error for DataFileError {}
+
+impl From<io::Error> for DataFileError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<ron::Error> for DataFileError {
+    fn from(value: ron::Error) -> Self {
+        Self::Serialize(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for DataFileError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Deserialize(value)
+    }
+}
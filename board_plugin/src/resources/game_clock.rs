@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+/// Time spent playing the current board, only ticking between the first reveal and the
+/// end of the game while the board is running
+#[derive(Debug, Default, Clone, Resource)]
+pub struct GameClock {
+    elapsed: Duration,
+    running: bool,
+}
+
+impl GameClock {
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        Self {
+            elapsed,
+            running: false,
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    pub fn start(&mut self) {
+        self.running = true;
+    }
+
+    pub fn stop(&mut self) {
+        self.running = false;
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if self.running {
+            self.elapsed += delta;
+        }
+    }
+}
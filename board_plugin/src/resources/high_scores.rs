@@ -0,0 +1,104 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    DataFileError, Difficulty,
+    data_file::{read_ron, write_ron},
+};
+
+/// Entries kept per difficulty
+pub const HIGH_SCORE_ENTRIES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub time: Duration,
+    /// Seconds since the Unix epoch
+    pub date: u64,
+    pub seed: u64,
+    pub three_bv: u32,
+    pub hints_used: bool,
+}
+
+/// Best times per difficulty, fastest first
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Resource)]
+pub struct HighScores {
+    tables: BTreeMap<Difficulty, Vec<HighScore>>,
+}
+
+/// A qualifying score waiting for the player's name
+#[derive(Debug, Clone, Resource)]
+pub struct NameEntry {
+    pub difficulty: Difficulty,
+    pub score: HighScore,
+}
+
+impl HighScore {
+    /// A score dated now, without a name yet
+    pub fn now(time: Duration, seed: u64, three_bv: u32, hints_used: bool) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            name: String::new(),
+            time,
+            date,
+            seed,
+            three_bv,
+            hints_used,
+        }
+    }
+
+    /// The date as `YYYY-MM-DD`, in UTC
+    pub fn date_string(&self) -> String {
+        // Days to civil date, from Howard Hinnant's `civil_from_days`
+        let z = (self.date / 86400) as i64 + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+impl HighScores {
+    pub fn table(&self, difficulty: &Difficulty) -> &[HighScore] {
+        self.tables.get(difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether a game won in `time` makes it into the table
+    pub fn qualifies(&self, difficulty: &Difficulty, time: Duration) -> bool {
+        let table = self.table(difficulty);
+        table.len() < HIGH_SCORE_ENTRIES || table.last().is_some_and(|last| time < last.time)
+    }
+
+    /// Inserts a score, returning its rank if it stays in the table
+    pub fn insert(&mut self, difficulty: Difficulty, score: HighScore) -> Option<usize> {
+        let table = self.tables.entry(difficulty).or_default();
+        let rank = table.partition_point(|s| s.time <= score.time);
+        if rank >= HIGH_SCORE_ENTRIES {
+            return None;
+        }
+        table.insert(rank, score);
+        table.truncate(HIGH_SCORE_ENTRIES);
+        Some(rank)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DataFileError> {
+        write_ron(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataFileError> {
+        read_ron(path)
+    }
+}
@@ -1,6 +1,3 @@
-pub(crate) mod tile;
-pub(crate) mod tile_map;
-
 mod board_options;
 
 pub use board_options::*;
@@ -12,3 +9,35 @@ pub use board::Board;
 mod board_assets;
 
 pub use board_assets::*;
+
+mod probability_overlay;
+
+pub use probability_overlay::ProbabilityOverlay;
+
+mod game_clock;
+
+pub use game_clock::GameClock;
+
+mod data_file;
+
+pub use data_file::DataFileError;
+
+mod saved_game;
+
+pub use saved_game::SavedGame;
+
+mod replay;
+
+pub use replay::{ReplayFile, ReplayPlayback};
+
+mod statistics;
+
+pub use statistics::{Difficulty, DifficultyStats, Statistics};
+
+mod high_scores;
+
+pub use high_scores::{HIGH_SCORE_ENTRIES, HighScore, HighScores, NameEntry};
+
+mod board_config;
+
+pub use board_config::*;
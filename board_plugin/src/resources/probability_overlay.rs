@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+
+/// Tints the tile covers by their mine probability while enabled
+#[derive(Debug, Clone, Resource)]
+pub struct ProbabilityOverlay {
+    pub enabled: bool,
+    pub safe_color: Color,
+    pub mine_color: Color,
+    /// How much of the heat color is blended into the cover color
+    pub strength: f32,
+}
+
+impl Default for ProbabilityOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            safe_color: Color::linear_rgb(0., 1., 0.),
+            mine_color: Color::linear_rgb(1., 0., 0.),
+            strength: 0.6,
+        }
+    }
+}
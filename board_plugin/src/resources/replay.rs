@@ -0,0 +1,70 @@
+use std::{path::Path, time::Duration};
+
+use bevy::prelude::*;
+use minesweeper_core::{Replay, TimedAction};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    Board, BoardOptions, DataFileError,
+    data_file::{read_ron, write_ron},
+};
+
+/// A recorded game and the options it was played with. When present as a resource, the next
+/// board is rebuilt from it and played back
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct ReplayFile {
+    pub options: BoardOptions,
+    pub replay: Replay,
+}
+
+/// Feeds a replay back into the board at `speed` times its original pace
+#[derive(Debug, Clone, Resource)]
+pub struct ReplayPlayback {
+    replay: Replay,
+    next: usize,
+    elapsed: Duration,
+    pub speed: f32,
+}
+
+impl ReplayFile {
+    pub fn capture(board: &Board) -> Self {
+        Self {
+            options: board.options.clone(),
+            replay: board.replay.clone(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DataFileError> {
+        write_ron(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataFileError> {
+        read_ron(path)
+    }
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next: 0,
+            elapsed: Duration::ZERO,
+            speed: 1.,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.actions.len()
+    }
+
+    /// Moves the playback forward, returning the actions that are now due
+    pub fn advance(&mut self, delta: Duration) -> &[TimedAction] {
+        self.elapsed += delta.mul_f32(self.speed);
+        let start = self.next;
+        let actions = &self.replay.actions;
+        while self.next < actions.len() && actions[self.next].time <= self.elapsed {
+            self.next += 1;
+        }
+        &actions[start..self.next]
+    }
+}
@@ -0,0 +1,93 @@
+use std::{fs, io, path::Path, time::Duration};
+
+use bevy::prelude::*;
+use minesweeper_core::{ClickCounts, Game, Replay};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    Board, BoardOptions, DataFileError, GameClock,
+    data_file::{read_ron, write_ron},
+};
+
+/// A game in progress. When present as a resource, the next board is rebuilt from it
+/// instead of generating a new one
+#[derive(Debug, Clone, Serialize, Deserialize, Resource)]
+pub struct SavedGame {
+    /// Format of the save, older saves cannot be read back
+    #[serde(default)]
+    pub version: u32,
+    pub options: BoardOptions,
+    pub game: Game,
+    pub elapsed: Duration,
+    pub hints_used: u32,
+    #[serde(default)]
+    pub replay: Option<Replay>,
+    #[serde(default)]
+    pub clicks: ClickCounts,
+    #[serde(default)]
+    pub effective_clicks: u32,
+}
+
+/// Only the version of a save, read before the rest which may be in an older format
+#[derive(Deserialize)]
+#[serde(rename = "SavedGame")]
+struct SaveVersion {
+    #[serde(default)]
+    version: u32,
+}
+
+impl SavedGame {
+    /// Saves before tiles could hold several mines and boards were stored in chunks have no
+    /// version, and are reported as `DataFileError::Outdated`
+    pub const VERSION: u32 = 1;
+
+    /// A game that has not been played yet
+    pub fn new(options: BoardOptions, game: Game) -> Self {
+        Self {
+            version: Self::VERSION,
+            replay: Some(Replay::new(&game)),
+            options,
+            game,
+            elapsed: Duration::ZERO,
+            hints_used: 0,
+            clicks: ClickCounts::default(),
+            effective_clicks: 0,
+        }
+    }
+
+    pub fn capture(board: &Board, clock: &GameClock) -> Self {
+        Self {
+            version: Self::VERSION,
+            options: board.options.clone(),
+            game: board.game.clone(),
+            elapsed: clock.elapsed(),
+            hints_used: board.hints_used,
+            replay: Some(board.replay.clone()),
+            clicks: board.clicks,
+            effective_clicks: board.effective_clicks,
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DataFileError> {
+        write_ron(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataFileError> {
+        let SaveVersion { version } = read_ron(&path)?;
+        if version != Self::VERSION {
+            return Err(DataFileError::Outdated {
+                version,
+                expected: Self::VERSION,
+            });
+        }
+        read_ron(path)
+    }
+
+    /// Removes a save file, if there is one
+    pub fn delete(path: impl AsRef<Path>) -> Result<(), DataFileError> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::{collections::BTreeMap, fmt::Display, path::Path, time::Duration};
+
+use bevy::prelude::*;
+use minesweeper_core::{Game, GenerationMode, Grid, NeighborhoodRule, Topology};
+use serde::{Deserialize, Serialize};
+
+use crate::resources::{
+    DataFileError,
+    data_file::{read_ron, write_ron},
+};
+
+/// Board configuration that games are grouped by in statistics and scores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Difficulty {
+    pub map_size: (u16, u16),
+    /// Mines of the board, or average mines per chunk of an endless board
+    pub bomb_count: u16,
+    #[serde(default)]
+    pub topology: Topology,
+    #[serde(default)]
+    pub grid: Grid,
+    /// Always `Moore` on hex grids, which ignore it
+    #[serde(default)]
+    pub neighborhood: NeighborhoodRule,
+    #[serde(default = "single_mine")]
+    pub max_mines_per_tile: u8,
+    #[serde(default)]
+    pub generation: GenerationMode,
+    #[serde(default)]
+    pub endless: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DifficultyStats {
+    pub won: u32,
+    pub lost: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub best_time: Option<Duration>,
+    /// Sum of the winning times, for the average
+    pub total_time: Duration,
+}
+
+/// Results of every finished game, per difficulty
+#[derive(Debug, Default, Clone, Serialize, Deserialize, Resource)]
+pub struct Statistics {
+    difficulties: BTreeMap<Difficulty, DifficultyStats>,
+}
+
+impl Difficulty {
+    pub fn of(game: &Game) -> Self {
+        let options = game.options();
+        Self {
+            map_size: (options.width, options.height),
+            bomb_count: options.bomb_count,
+            topology: options.topology,
+            grid: options.grid,
+            neighborhood: match options.grid {
+                Grid::Square => options.neighborhood,
+                Grid::Hex => NeighborhoodRule::default(),
+            },
+            max_mines_per_tile: options.max_mines_per_tile,
+            generation: options.generation,
+            endless: options.endless,
+        }
+    }
+}
+
+/// Games recorded before tiles could hold several mines had one per tile
+fn single_mine() -> u8 {
+    1
+}
+
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.endless {
+            true => write!(f, "Endless, {} mines per chunk", self.bomb_count)?,
+            false => write!(
+                f,
+                "{}x{}, {} mines",
+                self.map_size.0, self.map_size.1, self.bomb_count
+            )?,
+        }
+        // Endless boards always wrap
+        if self.topology == Topology::Toroidal && !self.endless {
+            write!(f, ", toroidal")?;
+        }
+        if self.grid == Grid::Hex {
+            write!(f, ", hex")?;
+        }
+        if self.neighborhood != NeighborhoodRule::Moore {
+            write!(f, ", {:?} neighbours", self.neighborhood)?;
+        }
+        if self.max_mines_per_tile > 1 {
+            write!(f, ", up to {} mines per tile", self.max_mines_per_tile)?;
+        }
+        if self.generation == GenerationMode::NoGuess {
+            write!(f, ", no guess")?;
+        }
+        Ok(())
+    }
+}
+
+impl DifficultyStats {
+    pub fn played(&self) -> u32 {
+        self.won + self.lost
+    }
+
+    pub fn average_time(&self) -> Option<Duration> {
+        (self.won > 0).then(|| self.total_time / self.won)
+    }
+}
+
+impl Statistics {
+    pub fn record_win(&mut self, difficulty: Difficulty, time: Duration) {
+        let stats = self.difficulties.entry(difficulty).or_default();
+        stats.won += 1;
+        stats.current_streak += 1;
+        stats.best_streak = stats.best_streak.max(stats.current_streak);
+        stats.best_time = Some(stats.best_time.map_or(time, |best| best.min(time)));
+        stats.total_time += time;
+    }
+
+    pub fn record_loss(&mut self, difficulty: Difficulty) {
+        let stats = self.difficulties.entry(difficulty).or_default();
+        stats.lost += 1;
+        stats.current_streak = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Difficulty, &DifficultyStats)> {
+        self.difficulties.iter()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), DataFileError> {
+        write_ron(self, path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataFileError> {
+        read_ron(path)
+    }
+}
@@ -0,0 +1,299 @@
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    log,
+    prelude::*,
+    state::state::FreelyMutableState,
+};
+
+use crate::{
+    components::{CustomField, CustomFieldText, MenuButton, MenuErrorText, MenuScreen},
+    resources::{BoardAssets, BoardOptions, BoardOptionsError},
+    screens::MenuStartState,
+};
+
+const BUTTON_COLOR: Color = Color::srgb(0.2, 0.2, 0.2);
+const HOVERED_BUTTON_COLOR: Color = Color::srgb(0.35, 0.35, 0.35);
+const ERROR_COLOR: Color = Color::srgb(0.9, 0.3, 0.3);
+
+/// Values typed in the custom game form
+#[derive(Debug, Clone, Resource)]
+pub struct CustomGame {
+    width: u16,
+    height: u16,
+    mines: u16,
+    focused: Option<CustomField>,
+    /// Why the last start failed, until the form is edited
+    error: Option<BoardOptionsError>,
+}
+
+impl CustomGame {
+    fn value(&self, field: CustomField) -> u16 {
+        match field {
+            CustomField::Width => self.width,
+            CustomField::Height => self.height,
+            CustomField::Mines => self.mines,
+        }
+    }
+
+    fn value_mut(&mut self, field: CustomField) -> &mut u16 {
+        match field {
+            CustomField::Width => &mut self.width,
+            CustomField::Height => &mut self.height,
+            CustomField::Mines => &mut self.mines,
+        }
+    }
+
+    fn label(&self, field: CustomField) -> String {
+        let name = match field {
+            CustomField::Width => "Width",
+            CustomField::Height => "Height",
+            CustomField::Mines => "Mines",
+        };
+        let cursor = if self.focused == Some(field) { "_" } else { "" };
+        format!("{}: {}{}", name, self.value(field), cursor)
+    }
+
+    fn error_message(&self) -> String {
+        self.error.map(|e| e.to_string()).unwrap_or_default()
+    }
+}
+
+pub fn spawn_menu(
+    mut commands: Commands,
+    board_options: Option<Res<BoardOptions>>,
+    board_assets: Option<Res<BoardAssets>>,
+) {
+    let options = board_options.map(|o| o.clone()).unwrap_or_default();
+    let custom = CustomGame {
+        width: options.map_size.0,
+        height: options.map_size.1,
+        mines: options.bomb_count,
+        focused: None,
+        error: None,
+    };
+    let font = TextFont {
+        font: board_assets
+            .map(|assets| assets.bomb_counter_font.clone())
+            .unwrap_or_default(),
+        font_size: 16.,
+        ..Default::default()
+    };
+    let buttons = [
+        (MenuButton::Beginner, "Beginner 9x9, 10 mines".to_string()),
+        (
+            MenuButton::Intermediate,
+            "Intermediate 16x16, 40 mines".to_string(),
+        ),
+        (MenuButton::Expert, "Expert 30x16, 99 mines".to_string()),
+        (
+            MenuButton::Endless,
+            "Endless, arrow keys to pan".to_string(),
+        ),
+        (
+            MenuButton::Field(CustomField::Width),
+            custom.label(CustomField::Width),
+        ),
+        (
+            MenuButton::Field(CustomField::Height),
+            custom.label(CustomField::Height),
+        ),
+        (
+            MenuButton::Field(CustomField::Mines),
+            custom.label(CustomField::Mines),
+        ),
+        (MenuButton::StartCustom, "Start custom game".to_string()),
+    ];
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(8.),
+                ..Default::default()
+            },
+            Name::new("Menu Screen"),
+            MenuScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("New game"), font.clone()));
+            for (button, label) in buttons {
+                let mut entity = parent.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(360.),
+                        padding: UiRect::all(Val::Px(8.)),
+                        justify_content: JustifyContent::Center,
+                        ..Default::default()
+                    },
+                    BackgroundColor(BUTTON_COLOR),
+                    button,
+                ));
+                entity.with_children(|parent| match button {
+                    MenuButton::Field(field) => {
+                        parent.spawn((Text::new(label), font.clone(), CustomFieldText(field)));
+                    }
+                    _ => {
+                        parent.spawn((Text::new(label), font.clone()));
+                    }
+                });
+            }
+            parent.spawn((
+                Text::default(),
+                font.clone(),
+                TextColor(ERROR_COLOR),
+                MenuErrorText,
+            ));
+        });
+    commands.insert_resource(custom);
+}
+
+pub fn menu_buttons<T: FreelyMutableState>(
+    mut commands: Commands,
+    interactions: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+    mut custom: ResMut<CustomGame>,
+    board_options: Option<Res<BoardOptions>>,
+    start_state: Res<MenuStartState<T>>,
+    mut next_state: ResMut<NextState<T>>,
+) {
+    for (interaction, button) in interactions.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let preset = match button {
+            MenuButton::Beginner => BoardOptions::beginner(),
+            MenuButton::Intermediate => BoardOptions::intermediate(),
+            MenuButton::Expert => BoardOptions::expert(),
+            MenuButton::Endless => BoardOptions::endless(),
+            MenuButton::Field(field) => {
+                custom.focused = Some(*field);
+                continue;
+            }
+            MenuButton::StartCustom => custom_options(&custom),
+        };
+        match start_game(&mut commands, board_options.as_deref(), preset) {
+            Ok(()) => next_state.set(start_state.0.clone()),
+            Err(e) => {
+                log::warn!("Invalid custom game: {}", e);
+                custom.error = Some(e);
+            }
+        }
+    }
+}
+
+pub fn custom_field_input<T: FreelyMutableState>(
+    mut commands: Commands,
+    mut keyboard_evr: EventReader<KeyboardInput>,
+    mut custom: ResMut<CustomGame>,
+    board_options: Option<Res<BoardOptions>>,
+    start_state: Res<MenuStartState<T>>,
+    mut next_state: ResMut<NextState<T>>,
+    mut field_texts: Query<(&mut Text, &CustomFieldText)>,
+) {
+    for event in keyboard_evr.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let Some(field) = custom.focused else {
+            continue;
+        };
+        match &event.logical_key {
+            Key::Character(c) => {
+                if let Some(digit) = c.chars().next().and_then(|c| c.to_digit(10)) {
+                    let value = custom.value_mut(field);
+                    *value = value.saturating_mul(10).saturating_add(digit as u16);
+                    custom.error = None;
+                }
+            }
+            Key::Backspace => {
+                *custom.value_mut(field) /= 10;
+                custom.error = None;
+            }
+            Key::Tab => {
+                custom.focused = Some(match field {
+                    CustomField::Width => CustomField::Height,
+                    CustomField::Height => CustomField::Mines,
+                    CustomField::Mines => CustomField::Width,
+                })
+            }
+            Key::Enter => {
+                let preset = custom_options(&custom);
+                match start_game(&mut commands, board_options.as_deref(), preset) {
+                    Ok(()) => next_state.set(start_state.0.clone()),
+                    Err(e) => {
+                        log::warn!("Invalid custom game: {}", e);
+                        custom.error = Some(e);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    if custom.is_changed() {
+        for (mut text, CustomFieldText(field)) in field_texts.iter_mut() {
+            text.0 = custom.label(*field);
+        }
+    }
+}
+
+pub fn show_error(custom: Res<CustomGame>, mut error_texts: Query<&mut Text, With<MenuErrorText>>) {
+    if !custom.is_changed() {
+        return;
+    }
+    for mut text in error_texts.iter_mut() {
+        text.0 = custom.error_message();
+    }
+}
+
+pub fn button_colors(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut color) in buttons.iter_mut() {
+        color.0 = match interaction {
+            Interaction::Hovered | Interaction::Pressed => HOVERED_BUTTON_COLOR,
+            Interaction::None => BUTTON_COLOR,
+        };
+    }
+}
+
+fn custom_options(custom: &CustomGame) -> BoardOptions {
+    BoardOptions {
+        map_size: (custom.width, custom.height),
+        bomb_count: custom.mines,
+        ..Default::default()
+    }
+}
+
+/// Uses the size and mines of `preset`, keeping the rest of the current options
+fn start_game(
+    commands: &mut Commands,
+    current: Option<&BoardOptions>,
+    preset: BoardOptions,
+) -> Result<(), BoardOptionsError> {
+    let options = BoardOptions {
+        map_size: preset.map_size,
+        bomb_count: preset.bomb_count,
+        endless_density: preset.endless_density,
+        board_file: None,
+        ..current.cloned().unwrap_or(preset)
+    };
+    options.validate()?;
+    if let Some(density) = options.endless_density {
+        log::info!(
+            "Starting an endless game with a mine density of {}",
+            density
+        );
+        commands.insert_resource(options);
+        return Ok(());
+    }
+    log::info!(
+        "Starting a {}x{} game with {} mines",
+        options.map_size.0,
+        options.map_size.1,
+        options.bomb_count
+    );
+    commands.insert_resource(options);
+    Ok(())
+}
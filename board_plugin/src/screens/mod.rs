@@ -0,0 +1,52 @@
+use bevy::{prelude::*, state::state::FreelyMutableState};
+
+use crate::components::{MenuScreen, StatsScreen};
+
+mod menu;
+mod stats;
+
+/// Shows the statistics of every played difficulty while in `state`
+pub struct StatsScreenPlugin<T> {
+    pub state: T,
+}
+
+/// Shows the difficulty presets and a custom game form while in `state`, moving to
+/// `start_state` once a game is picked
+pub struct MenuScreenPlugin<T> {
+    pub state: T,
+    pub start_state: T,
+}
+
+#[derive(Debug, Clone, Resource)]
+struct MenuStartState<T>(T);
+
+impl<T: States> Plugin for StatsScreenPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(self.state.clone()), stats::spawn_stats_screen)
+            .add_systems(OnExit(self.state.clone()), despawn_screen::<StatsScreen>);
+    }
+}
+
+impl<T: FreelyMutableState> Plugin for MenuScreenPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MenuStartState(self.start_state.clone()))
+            .add_systems(OnEnter(self.state.clone()), menu::spawn_menu)
+            .add_systems(
+                Update,
+                (
+                    menu::menu_buttons::<T>,
+                    menu::custom_field_input::<T>,
+                    menu::show_error,
+                    menu::button_colors,
+                )
+                    .run_if(in_state(self.state.clone())),
+            )
+            .add_systems(OnExit(self.state.clone()), despawn_screen::<MenuScreen>);
+    }
+}
+
+fn despawn_screen<C: Component>(mut commands: Commands, screen: Query<Entity, With<C>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn();
+    }
+}
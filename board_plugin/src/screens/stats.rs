@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    components::StatsScreen,
+    resources::{BoardAssets, Statistics},
+};
+
+pub fn spawn_stats_screen(
+    mut commands: Commands,
+    statistics: Res<Statistics>,
+    board_assets: Option<Res<BoardAssets>>,
+) {
+    let font = TextFont {
+        font: board_assets
+            .map(|assets| assets.bomb_counter_font.clone())
+            .unwrap_or_default(),
+        font_size: 16.,
+        ..Default::default()
+    };
+    let mut lines = vec!["Statistics".to_string()];
+    for (difficulty, stats) in statistics.iter() {
+        lines.push(format!(
+            "{}: played {}, won {} ({:.0}%), lost {}, streak {} (best {}), best {}, average {}",
+            difficulty,
+            stats.played(),
+            stats.won,
+            stats.won as f32 * 100. / stats.played().max(1) as f32,
+            stats.lost,
+            stats.current_streak,
+            stats.best_streak,
+            format_time(stats.best_time),
+            format_time(stats.average_time()),
+        ));
+    }
+    if lines.len() == 1 {
+        lines.push("No games played yet".to_string());
+    }
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                padding: UiRect::all(Val::Px(16.)),
+                ..Default::default()
+            },
+            Name::new("Stats Screen"),
+            StatsScreen,
+        ))
+        .with_children(|parent| {
+            for line in lines {
+                parent.spawn((Text::new(line), font.clone()));
+            }
+        });
+}
+
+fn format_time(time: Option<Duration>) -> String {
+    match time {
+        Some(time) => format!("{:.1}s", time.as_secs_f32()),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,42 @@
+use bevy::log;
+use bevy::platform::collections::HashMap;
+use minesweeper_core::{
+    Coordinate,
+    hint::{self, Hint},
+    probability,
+};
+
+use crate::resources::Board;
+
+/// The solver only reasons about one mine per tile, over a whole board
+fn is_supported(board: &Board) -> bool {
+    if board.game.tile_map().is_endless() {
+        log::debug!("The solver does not support endless boards");
+        return false;
+    }
+    let supported = board.game.options().max_mines_per_tile == 1;
+    if !supported {
+        log::debug!("The solver does not support tiles with several mines");
+    }
+    supported
+}
+
+/// Exact mine probability of every covered tile, `None` if it cannot be computed
+pub fn probabilities(board: &Board) -> Option<HashMap<Coordinate, f64>> {
+    if !is_supported(board) {
+        return None;
+    }
+    let probabilities = probability::mine_probabilities(&board.game.view());
+    if probabilities.is_none() {
+        log::warn!("Could not compute mine probabilities for the current board");
+    }
+    probabilities.map(|p| p.into_iter().collect())
+}
+
+/// A tile the player can uncover, provably safe when possible
+pub fn hint(board: &Board) -> Option<Hint> {
+    if !is_supported(board) {
+        return None;
+    }
+    hint::hint(&board.game.view())
+}
@@ -0,0 +1,37 @@
+use bevy::{log, prelude::*};
+
+use crate::resources::{Board, BoardConfig, BoardConfigHandle};
+
+pub fn apply_board_config(
+    mut commands: Commands,
+    mut config_evr: EventReader<AssetEvent<BoardConfig>>,
+    handle: Option<Res<BoardConfigHandle>>,
+    configs: Res<Assets<BoardConfig>>,
+    board: Option<Res<Board>>,
+) {
+    let Some(handle) = handle else {
+        config_evr.clear();
+        return;
+    };
+    let updated = config_evr
+        .read()
+        .filter(|event| {
+            event.is_loaded_with_dependencies(&handle.0) || event.is_modified(&handle.0)
+        })
+        .count()
+        > 0;
+    if !updated {
+        return;
+    }
+    let Some(config) = configs.get(&handle.0) else {
+        return;
+    };
+    log::info!("Applying board config \"{}\"", config.assets.label);
+    commands.insert_resource(config.options.clone());
+    commands.insert_resource(config.assets.clone());
+    if let Some(board) = board {
+        log::info!("Regenerating board");
+        commands.entity(board.entity).despawn();
+        commands.remove_resource::<Board>();
+    }
+}
@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+use minesweeper_core::ChunkCoordinate;
+
+use crate::{
+    events::TileChangeEvent,
+    resources::{Board, BoardAssets},
+    systems::render::spawn_tiles,
+};
+
+/// Tiles the camera moves per second
+const PAN_SPEED: f32 = 20.;
+
+pub fn is_endless(board: Res<Board>) -> bool {
+    board.game.tile_map().is_endless()
+}
+
+/// Moves the camera with the arrow keys, starting each board on its middle tile
+pub fn pan_camera(
+    keys: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    board: Res<Board>,
+    mut camera: Query<&mut Transform, With<Camera2d>>,
+) {
+    let mut direction = Vec2::ZERO;
+    for (key, step) in [
+        (KeyCode::ArrowLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, Vec2::X),
+        (KeyCode::ArrowDown, Vec2::NEG_Y),
+        (KeyCode::ArrowUp, Vec2::Y),
+    ] {
+        if keys.pressed(key) {
+            direction += step;
+        }
+    }
+    let offset = direction.normalize_or_zero() * PAN_SPEED * board.tile_size * time.delta_secs();
+    for mut transform in camera.iter_mut() {
+        if board.is_added() {
+            transform.translation.x = 0.;
+            transform.translation.y = 0.;
+        }
+        transform.translation += offset.extend(0.);
+    }
+}
+
+/// Spawns the tiles of the chunks around the view as the game shows them, and despawns the
+/// chunks that went out of it
+pub fn stream_chunks(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_assets: Res<BoardAssets>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    mut tile_change_ewr: EventWriter<TileChangeEvent>,
+) {
+    let Ok((camera, transform)) = camera.single() else {
+        return;
+    };
+    let Some(view) = camera.logical_viewport_size() else {
+        return;
+    };
+    let layout = board.layout();
+    let center = transform.translation().xy() - board.bounds.position;
+    // Half a chunk of margin, so chunks are ready before they scroll into view
+    let half_view = view / 2. + ChunkCoordinate::SIZE as f32 * board.tile_size / 2.;
+    let (Some(min), Some(max)) = (
+        layout.coordinate_at(center - half_view),
+        layout.coordinate_at(center + half_view),
+    ) else {
+        return;
+    };
+    let (min, max) = (min.chunk(), max.chunk());
+    let near = |chunk: &ChunkCoordinate| {
+        (min.x..=max.x).contains(&chunk.x) && (min.y..=max.y).contains(&chunk.y)
+    };
+    let far: Vec<ChunkCoordinate> = board.chunks.keys().filter(|c| !near(c)).copied().collect();
+    for chunk in far {
+        if let Some(entity) = board.chunks.remove(&chunk) {
+            commands.entity(entity).despawn();
+        }
+        for coordinate in chunk.coordinates() {
+            board.tiles.remove(&coordinate);
+            board.covered_tiles.remove(&coordinate);
+        }
+    }
+    let padding = board.options.tile_padding;
+    for x in min.x..=max.x {
+        for y in min.y..=max.y {
+            let chunk = ChunkCoordinate { x, y };
+            if board.chunks.contains_key(&chunk) {
+                continue;
+            }
+            let coordinates: Vec<_> = chunk
+                .coordinates()
+                .filter(|&c| board.game.tile_map().contains(c))
+                .collect();
+            let board = &mut *board;
+            let entity = commands
+                .spawn((
+                    Name::new(format!("Chunk ({}, {})", x, y)),
+                    Transform::default(),
+                    Visibility::default(),
+                    ChildOf(board.entity),
+                ))
+                .with_children(|parent| {
+                    spawn_tiles(
+                        parent,
+                        coordinates.iter().copied(),
+                        layout,
+                        padding,
+                        &board_assets,
+                        &mut board.tiles,
+                        &mut board.covered_tiles,
+                    );
+                })
+                .id();
+            board.chunks.insert(chunk, entity);
+            for &coordinate in &coordinates {
+                for change in board.game.restored_changes(coordinate) {
+                    tile_change_ewr.write(TileChangeEvent(change));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use bevy::{
+    input::keyboard::{Key, KeyboardInput},
+    log,
+    prelude::*,
+};
+
+use crate::{
+    components::{HighScorePrompt, HighScoreTable, NameText},
+    events::BoardCompletedEvent,
+    resources::{Board, BoardAssets, Difficulty, HighScore, HighScores, NameEntry, ReplayPlayback},
+};
+
+const MAX_NAME_LENGTH: usize = 16;
+
+pub fn check_high_score(
+    mut commands: Commands,
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    playback: Option<Res<ReplayPlayback>>,
+    high_scores: Res<HighScores>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+) {
+    for event in board_completed_evr.read() {
+        let difficulty = Difficulty::of(&board.game);
+        if playback.is_some() || !high_scores.qualifies(&difficulty, event.metrics.time) {
+            continue;
+        }
+        let score = HighScore::now(
+            event.metrics.time,
+            board.seed(),
+            event.metrics.three_bv,
+            event.hints_used > 0,
+        );
+        commands.insert_resource(NameEntry { difficulty, score });
+        commands
+            .spawn((
+                panel_node(),
+                BackgroundColor(Color::BLACK.with_alpha(0.8)),
+                Name::new("High Score Prompt"),
+                HighScorePrompt,
+            ))
+            .with_children(|parent| {
+                let font = panel_font(&board_assets);
+                parent.spawn((Text::new("New high score! Enter your name:"), font.clone()));
+                parent.spawn((Text::new("_"), font, NameText));
+            });
+    }
+}
+
+pub fn name_entry_input(
+    mut commands: Commands,
+    mut keyboard_evr: EventReader<KeyboardInput>,
+    name_entry: Option<ResMut<NameEntry>>,
+    mut high_scores: ResMut<HighScores>,
+    board_assets: Res<BoardAssets>,
+    prompt: Query<Entity, With<HighScorePrompt>>,
+    mut name_text: Query<&mut Text, With<NameText>>,
+) {
+    let Some(mut name_entry) = name_entry else {
+        keyboard_evr.clear();
+        return;
+    };
+    let mut submitted = false;
+    for event in keyboard_evr.read() {
+        if !event.state.is_pressed() {
+            continue;
+        }
+        let name = &mut name_entry.score.name;
+        match &event.logical_key {
+            Key::Enter => submitted = true,
+            Key::Backspace => {
+                name.pop();
+            }
+            Key::Space if name.len() < MAX_NAME_LENGTH => name.push(' '),
+            Key::Character(c) if name.len() + c.len() <= MAX_NAME_LENGTH => name.push_str(c),
+            _ => (),
+        }
+    }
+    if !submitted {
+        for mut text in name_text.iter_mut() {
+            text.0 = format!("{}_", name_entry.score.name);
+        }
+        return;
+    }
+    let NameEntry {
+        difficulty,
+        mut score,
+    } = name_entry.clone();
+    if score.name.trim().is_empty() {
+        score.name = "Anonymous".to_string();
+    }
+    commands.remove_resource::<NameEntry>();
+    for entity in prompt.iter() {
+        commands.entity(entity).despawn();
+    }
+    let rank = high_scores.insert(difficulty, score);
+    log::info!("High score saved at rank {:?}", rank.map(|r| r + 1));
+    spawn_high_score_table(
+        &mut commands,
+        &board_assets,
+        &difficulty,
+        high_scores.table(&difficulty),
+        rank,
+    );
+}
+
+fn spawn_high_score_table(
+    commands: &mut Commands,
+    board_assets: &BoardAssets,
+    difficulty: &Difficulty,
+    table: &[HighScore],
+    highlighted: Option<usize>,
+) {
+    let font = panel_font(board_assets);
+    commands
+        .spawn((
+            panel_node(),
+            BackgroundColor(Color::BLACK.with_alpha(0.8)),
+            Name::new("High Score Table"),
+            HighScoreTable,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("High scores, {}", difficulty)),
+                font.clone(),
+            ));
+            for (rank, score) in table.iter().enumerate() {
+                let line = format!(
+                    "{:>2}. {:<16} {:>7.2}s  3BV {:<4} {}  seed {}{}",
+                    rank + 1,
+                    score.name,
+                    score.time.as_secs_f32(),
+                    score.three_bv,
+                    score.date_string(),
+                    score.seed,
+                    if score.hints_used { "  (hints)" } else { "" },
+                );
+                let color = if highlighted == Some(rank) {
+                    TextColor(board_assets.hint_material.color.with_alpha(1.))
+                } else {
+                    TextColor::default()
+                };
+                parent.spawn((Text::new(line), font.clone(), color));
+            }
+        });
+}
+
+pub fn despawn_high_scores(
+    mut commands: Commands,
+    prompt: Query<Entity, With<HighScorePrompt>>,
+    table: Query<Entity, With<HighScoreTable>>,
+) {
+    commands.remove_resource::<NameEntry>();
+    for entity in prompt.iter().chain(table.iter()) {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn panel_node() -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.),
+        height: Val::Percent(100.),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        row_gap: Val::Px(8.),
+        ..Default::default()
+    }
+}
+
+fn panel_font(board_assets: &BoardAssets) -> TextFont {
+    TextFont {
+        font: board_assets.bomb_counter_font.clone(),
+        font_size: 14.,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::{log, prelude::*};
+
+use crate::{
+    components::Hint,
+    events::{HintRequestEvent, TileChangeEvent},
+    resources::{Board, BoardAssets},
+    solver,
+};
+
+pub fn hint_handler(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_assets: Res<BoardAssets>,
+    mut hint_request_evr: EventReader<HintRequestEvent>,
+    hints: Query<Entity, With<Hint>>,
+) {
+    if hint_request_evr.read().count() == 0 || board.game.state().is_over() {
+        return;
+    }
+    for entity in hints.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(hint) = solver::hint(&board) else {
+        log::info!("No hint available");
+        return;
+    };
+    board.hints_used += 1;
+    log::info!(
+        "Hint #{} on {}: {}",
+        board.hints_used,
+        hint.coordinate,
+        hint.reason
+    );
+    if let Some(&cover) = board.covered_tiles.get(&hint.coordinate) {
+        commands.entity(cover).with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    color: board_assets.hint_material.color,
+                    custom_size: Some(board.layout().sprite_size(0.)),
+                    image: board_assets.image(&board_assets.hint_material, board.layout().grid),
+                    ..Default::default()
+                },
+                Transform::from_xyz(0., 0., 2.),
+                Name::new("Hint"),
+                Hint,
+            ));
+        });
+    }
+    let font_size = board.tile_size / 2.;
+    commands.entity(board.entity).with_children(|parent| {
+        parent.spawn((
+            Text2d::new(hint.reason.to_string()),
+            TextFont {
+                font: board_assets.bomb_counter_font.clone(),
+                font_size,
+                ..Default::default()
+            },
+            TextColor(board_assets.hint_material.color),
+            Transform::from_xyz(board.bounds.size.x / 2., -font_size, 3.),
+            Name::new("Hint Explanation"),
+            Hint,
+        ));
+    });
+}
+
+pub fn clear_hints(
+    mut commands: Commands,
+    mut tile_change_evr: EventReader<TileChangeEvent>,
+    hints: Query<Entity, With<Hint>>,
+) {
+    if tile_change_evr.read().count() == 0 {
+        return;
+    }
+    for entity in hints.iter() {
+        commands.entity(entity).despawn();
+    }
+}
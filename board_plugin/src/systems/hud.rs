@@ -0,0 +1,98 @@
+use bevy::prelude::*;
+use minesweeper_core::GameState;
+
+use crate::{
+    components::{ClockText, Hud, MetricsText, MineCounterText},
+    resources::{Board, BoardAssets, GameClock},
+};
+
+pub fn spawn_hud(mut commands: Commands, board_assets: Res<BoardAssets>, hud: Query<&Hud>) {
+    if !hud.is_empty() {
+        return;
+    }
+    let font = TextFont {
+        font: board_assets.bomb_counter_font.clone(),
+        font_size: 20.,
+        ..Default::default()
+    };
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.),
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::all(Val::Px(8.)),
+                ..Default::default()
+            },
+            Name::new("HUD"),
+            Hud,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::default(), font.clone(), ClockText));
+            parent.spawn((Text::default(), font.clone(), MetricsText));
+            parent.spawn((Text::default(), font, MineCounterText));
+        });
+}
+
+pub fn tick_clock(time: Res<Time>, board: Res<Board>, mut clock: ResMut<GameClock>) {
+    let playing = board.game.is_generated() && !board.game.state().is_over();
+    if playing && !clock.is_running() {
+        clock.start();
+    } else if !playing && clock.is_running() {
+        clock.stop();
+    }
+    clock.tick(time.delta());
+}
+
+pub fn update_hud(
+    board: Res<Board>,
+    clock: Res<GameClock>,
+    mut clock_text: Query<&mut Text, (With<ClockText>, Without<MineCounterText>)>,
+    mut mine_text: Query<&mut Text, (With<MineCounterText>, Without<ClockText>)>,
+) {
+    let elapsed = clock.elapsed().as_secs();
+    let time = format!("{:02}:{:02}", elapsed / 60, elapsed % 60);
+    for mut text in clock_text.iter_mut() {
+        if text.0 != time {
+            text.0 = time.clone();
+        }
+    }
+    // Endless boards have no mine total, their score is the tiles cleared
+    let mines = match board.game.tile_map().is_endless() {
+        true => format!("Cleared: {}", board.game.cleared_tiles()),
+        false => {
+            let remaining =
+                board.game.options().bomb_count as i32 - board.game.total_flags() as i32;
+            format!("Mines: {}", remaining)
+        }
+    };
+    for mut text in mine_text.iter_mut() {
+        if text.0 != mines {
+            text.0 = mines.clone();
+        }
+    }
+}
+
+pub fn update_metrics_text(
+    board: Res<Board>,
+    clock: Res<GameClock>,
+    mut metrics_text: Query<&mut Text, With<MetricsText>>,
+) {
+    let metrics = match board.game.state() {
+        GameState::Won => board.metrics(clock.elapsed()).to_string(),
+        GameState::Lost if board.game.tile_map().is_endless() => {
+            format!("Game over, score {}", board.game.cleared_tiles())
+        }
+        _ => String::new(),
+    };
+    for mut text in metrics_text.iter_mut() {
+        if text.0 != metrics {
+            text.0 = metrics.clone();
+        }
+    }
+}
+
+pub fn despawn_hud(mut commands: Commands, hud: Query<Entity, With<Hud>>) {
+    for entity in hud.iter() {
+        commands.entity(entity).despawn();
+    }
+}
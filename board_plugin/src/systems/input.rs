@@ -1,39 +1,85 @@
 use crate::Board;
-use crate::events::{TileMarkEvent, TileTriggerEvent};
+use crate::events::{TileChordEvent, TileMarkEvent, TileTriggerEvent};
 use bevy::input::{ButtonState, mouse::MouseButtonInput};
 use bevy::log;
 use bevy::prelude::*;
 
+/// Buttons pressed over the board, which act once released unless they made a chord
+#[derive(Debug, Default)]
+pub struct HeldButtons {
+    held: Vec<MouseButton>,
+    chorded: bool,
+}
+
 pub fn input_handling(
-    window: Query<&Window>,
+    (window, camera): (Query<&Window>, Query<(&Camera, &GlobalTransform)>),
     board: Res<Board>,
+    mut held: Local<HeldButtons>,
     mut button_evr: EventReader<MouseButtonInput>,
     mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
     mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
 ) {
-    let Ok(window) = window.single() else {
+    let (Ok(window), Ok((camera, camera_transform))) = (window.single(), camera.single()) else {
         return;
     };
+    if board.game.state().is_over() {
+        button_evr.clear();
+        *held = HeldButtons::default();
+        return;
+    }
 
     for event in button_evr.read() {
-        if let ButtonState::Pressed = event.state {
-            let position = window.cursor_position();
-            if let Some(pos) = position {
-                let pos = Vec2::new(pos.x, window.height() - pos.y);
-                log::trace!("Mouse button pressed: {:?} at {}", event.button, pos);
-                let tile_coordinate = board.mouse_position(window, pos);
+        let position = window.cursor_position();
+        let world =
+            position.and_then(|pos| camera.viewport_to_world_2d(camera_transform, pos).ok());
+        let tile_coordinate = world.and_then(|pos| {
+            log::trace!(
+                "Mouse button {:?}: {:?} at {}",
+                event.state,
+                event.button,
+                pos
+            );
+            board.mouse_position(pos)
+        });
+        match event.state {
+            ButtonState::Pressed => {
+                if !held.held.contains(&event.button) {
+                    held.held.push(event.button);
+                }
+                // Left and right together chord once, however they were pressed
+                let chord = event.button == MouseButton::Middle
+                    || (held.held.contains(&MouseButton::Left)
+                        && held.held.contains(&MouseButton::Right));
+                if !chord || held.chorded {
+                    continue;
+                }
+                held.chorded = true;
                 if let Some(coordinate) = tile_coordinate {
-                    match event.button {
-                        MouseButton::Left => {
-                            log::info!("Trying to uncover tile on {}", coordinate);
-                            tile_trigger_ewr.write(TileTriggerEvent(coordinate));
-                        }
-                        MouseButton::Right => {
-                            log::info!("Trying to mark tile on {}", coordinate);
-                            tile_mark_ewr.write(TileMarkEvent(coordinate));
-                        }
-                        _ => (),
+                    log::info!("Trying to chord tile on {}", coordinate);
+                    tile_chord_ewr.write(TileChordEvent(coordinate));
+                }
+            }
+            ButtonState::Released => {
+                let was_held = held.held.contains(&event.button);
+                held.held.retain(|&button| button != event.button);
+                let chorded = held.chorded;
+                if held.held.is_empty() {
+                    held.chorded = false;
+                }
+                let Some(coordinate) = tile_coordinate.filter(|_| was_held && !chorded) else {
+                    continue;
+                };
+                match event.button {
+                    MouseButton::Left => {
+                        log::info!("Trying to uncover tile on {}", coordinate);
+                        tile_trigger_ewr.write(TileTriggerEvent(coordinate));
+                    }
+                    MouseButton::Right => {
+                        log::info!("Trying to mark tile on {}", coordinate);
+                        tile_mark_ewr.write(TileMarkEvent(coordinate));
                     }
+                    _ => (),
                 }
             }
         }
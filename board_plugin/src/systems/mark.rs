@@ -1,44 +1,22 @@
-use bevy::{log, prelude::*};
+use bevy::prelude::*;
 
 use crate::{
-    events::TileMarkEvent,
-    resources::{Board, BoardAssets},
+    events::{TileChangeEvent, TileMarkEvent},
+    resources::Board,
 };
 
 pub fn mark_tiles(
-    mut commands: Commands,
     mut board: ResMut<Board>,
-    board_assets: Res<BoardAssets>,
     mut tile_mark_event_rdr: EventReader<TileMarkEvent>,
-    query: Query<&Children>,
+    mut tile_change_ewr: EventWriter<TileChangeEvent>,
 ) {
     for event in tile_mark_event_rdr.read() {
-        if let Some((entity, mark)) = board.try_toggle_mark(&event.0) {
-            if mark {
-                commands.entity(entity).with_children(|parent| {
-                    parent.spawn((
-                        Sprite {
-                            color: board_assets.flag_material.color,
-                            custom_size: Some(Vec2::splat(board.tile_size)),
-                            image: board_assets.flag_material.texture.clone(),
-                            ..Default::default()
-                        },
-                        Transform::from_xyz(0., 0., 1.),
-                        Name::new("Flag"),
-                    ));
-                });
-            } else {
-                let children = match query.get(entity) {
-                    Ok(c) => c,
-                    Err(e) => {
-                        log::error!("Failed to retrive flag entity components: {}", e);
-                        continue;
-                    }
-                };
-                for child in children.iter() {
-                    commands.entity(child).despawn();
-                }
-            }
+        let changes = board.game.toggle_flag(event.0);
+        if !changes.is_empty() {
+            board.effective_clicks += 1;
+        }
+        for change in changes {
+            tile_change_ewr.write(TileChangeEvent(change));
         }
     }
 }
@@ -1,3 +1,12 @@
+pub mod config;
+pub mod endless;
+pub mod high_scores;
+pub mod hint;
+pub mod hud;
 pub mod input;
 pub mod mark;
+pub mod overlay;
+pub mod render;
+pub mod replay;
+pub mod statistics;
 pub mod uncover;
@@ -0,0 +1,37 @@
+use bevy::{color::Mix, prelude::*};
+
+use crate::{
+    events::TileChangeEvent,
+    resources::{Board, BoardAssets, ProbabilityOverlay},
+    solver,
+};
+
+pub fn update_probability_overlay(
+    board: Res<Board>,
+    board_assets: Res<BoardAssets>,
+    overlay: Res<ProbabilityOverlay>,
+    mut tile_change_evr: EventReader<TileChangeEvent>,
+    mut sprites: Query<&mut Sprite>,
+) {
+    let changed = tile_change_evr.read().count() > 0;
+    if !changed && !overlay.is_changed() && !board.is_added() {
+        return;
+    }
+    let probabilities = match overlay.enabled {
+        true => solver::probabilities(&board),
+        false => None,
+    };
+    let base = board_assets.covered_tile_material.color;
+    for (coordinate, &cover) in board.covered_tiles.iter() {
+        let Ok(mut sprite) = sprites.get_mut(cover) else {
+            continue;
+        };
+        sprite.color = match probabilities.as_ref().and_then(|p| p.get(coordinate)) {
+            Some(&p) => {
+                let heat = overlay.safe_color.mix(&overlay.mine_color, p as f32);
+                base.mix(&heat, overlay.strength)
+            }
+            None => base,
+        };
+    }
+}
@@ -0,0 +1,232 @@
+use bevy::{
+    ecs::relationship::RelatedSpawnerCommands, log, platform::collections::HashMap, prelude::*,
+};
+use minesweeper_core::{CellChange, Coordinate, Tile};
+
+use crate::{
+    components::{Bomb, BombNeighbor},
+    events::TileChangeEvent,
+    layout::Layout,
+    resources::{Board, BoardAssets, SpriteMaterial},
+};
+
+pub fn apply_tile_changes(
+    mut commands: Commands,
+    mut board: ResMut<Board>,
+    board_assets: Res<BoardAssets>,
+    mut tile_change_evr: EventReader<TileChangeEvent>,
+    children: Query<&Children>,
+) {
+    for event in tile_change_evr.read() {
+        match event.0 {
+            CellChange::Revealed { coordinate, tile } => {
+                match board.uncover_tile(&coordinate) {
+                    Some(e) => {
+                        log::debug!("Uncovered tile {} (entity: {:?})", coordinate, e);
+                        commands.entity(e).despawn();
+                    }
+                    None => log::debug!("Tried to uncover an already uncovered tile"),
+                }
+                if let Some(&entity) = board.tiles.get(&coordinate) {
+                    spawn_tile_content(&mut commands, entity, tile, &board, &board_assets);
+                }
+            }
+            CellChange::Flagged(coordinate) => {
+                let Some(&entity) = board.covered_tiles.get(&coordinate) else {
+                    continue;
+                };
+                // Flagging an already flagged tile raises its count on multi-mine boards
+                if let Ok(children) = children.get(entity) {
+                    for child in children.iter() {
+                        commands.entity(child).despawn();
+                    }
+                }
+                spawn_cover_overlay(
+                    &mut commands,
+                    entity,
+                    &board_assets.flag_material,
+                    &board_assets,
+                    board.layout(),
+                    "Flag",
+                );
+                let count = board.game.flag_count(coordinate);
+                if count > 1 {
+                    let font_size = board.layout().sprite_size(0.).x / 2.;
+                    commands.entity(entity).with_children(|parent| {
+                        parent.spawn(mine_count_text_bundle(count, &board_assets, font_size));
+                    });
+                }
+            }
+            CellChange::Unflagged(coordinate) => {
+                let Some(&entity) = board.covered_tiles.get(&coordinate) else {
+                    continue;
+                };
+                let children = match children.get(entity) {
+                    Ok(c) => c,
+                    Err(e) => {
+                        log::error!("Failed to retrive flag entity components: {}", e);
+                        continue;
+                    }
+                };
+                for child in children.iter() {
+                    commands.entity(child).despawn();
+                }
+            }
+            CellChange::Exploded(coordinate) => {
+                let Some(&entity) = board.tiles.get(&coordinate) else {
+                    continue;
+                };
+                // Inserted rather than changed, the tile may have been spawned this frame
+                let layout = board.layout();
+                let material = &board_assets.exploded_tile_material;
+                commands.entity(entity).insert(Sprite {
+                    color: material.color,
+                    custom_size: Some(layout.sprite_size(board.options.tile_padding)),
+                    image: board_assets.image(material, layout.grid),
+                    ..Default::default()
+                });
+            }
+            CellChange::WrongFlag(coordinate) => {
+                if let Some(&entity) = board.covered_tiles.get(&coordinate) {
+                    spawn_cover_overlay(
+                        &mut commands,
+                        entity,
+                        &board_assets.wrong_flag_material,
+                        &board_assets,
+                        board.layout(),
+                        "Wrong Flag",
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Spawns covered tiles, keeping their entities and the entities of their covers
+pub(crate) fn spawn_tiles(
+    parent: &mut RelatedSpawnerCommands<'_, ChildOf>,
+    coordinates: impl IntoIterator<Item = Coordinate>,
+    layout: Layout,
+    padding: f32,
+    board_assets: &BoardAssets,
+    tiles: &mut HashMap<Coordinate, Entity>,
+    covered_tiles: &mut HashMap<Coordinate, Entity>,
+) {
+    for coordinate in coordinates {
+        let mut tile = parent.spawn((
+            Sprite {
+                color: board_assets.tile_material.color,
+                custom_size: Some(layout.sprite_size(padding)),
+                image: board_assets.image(&board_assets.tile_material, layout.grid),
+                ..Default::default()
+            },
+            Transform::from_translation(layout.tile_center(coordinate).extend(1.)),
+            Name::new(format!("Tile ({}, {})", coordinate.x, coordinate.y)),
+            Visibility::default(),
+        ));
+        tiles.insert(coordinate, tile.id());
+        tile.with_children(|p| {
+            let entity = p
+                .spawn((
+                    Sprite {
+                        custom_size: Some(layout.sprite_size(padding)),
+                        color: board_assets.covered_tile_material.color,
+                        image: board_assets.image(&board_assets.covered_tile_material, layout.grid),
+                        ..Default::default()
+                    },
+                    Transform::from_xyz(0., 0., 2.),
+                    Name::new("Tile Cover"),
+                ))
+                .id();
+            covered_tiles.insert(coordinate, entity);
+        });
+    }
+}
+
+fn spawn_cover_overlay(
+    commands: &mut Commands,
+    cover: Entity,
+    material: &SpriteMaterial,
+    board_assets: &BoardAssets,
+    layout: Layout,
+    name: &'static str,
+) {
+    commands.entity(cover).with_children(|parent| {
+        parent.spawn((
+            Sprite {
+                color: material.color,
+                custom_size: Some(layout.sprite_size(0.)),
+                image: board_assets.image(material, layout.grid),
+                ..Default::default()
+            },
+            Transform::from_xyz(0., 0., 1.),
+            Name::new(name),
+        ));
+    });
+}
+
+fn spawn_tile_content(
+    commands: &mut Commands,
+    entity: Entity,
+    tile: Tile,
+    board: &Board,
+    board_assets: &BoardAssets,
+) {
+    let layout = board.layout();
+    let size = layout.sprite_size(board.options.tile_padding);
+    let mut tile_entity = commands.entity(entity);
+    match tile {
+        Tile::Bomb(count) => {
+            tile_entity.insert(Bomb);
+            tile_entity.with_children(|parent| {
+                parent.spawn((
+                    Sprite {
+                        custom_size: Some(size),
+                        color: board_assets.bomb_material.color,
+                        image: board_assets.image(&board_assets.bomb_material, layout.grid),
+                        ..Default::default()
+                    },
+                    Transform::from_xyz(0., 0., 1.),
+                ));
+                if count > 1 {
+                    parent.spawn(mine_count_text_bundle(count, board_assets, size.x / 2.));
+                }
+            });
+        }
+        Tile::BombNeighbor(v) => {
+            tile_entity.insert(BombNeighbor { count: v });
+            tile_entity.with_children(|parent| {
+                parent.spawn(bomb_count_text_bundle(v, board_assets, size.x / 2.));
+            });
+        }
+        Tile::Empty => (),
+    }
+}
+
+fn bomb_count_text_bundle(
+    count: u8,
+    board_assets: &BoardAssets,
+    font_size: f32,
+) -> (Text2d, TextFont, TextColor, Transform) {
+    let color = board_assets.bomb_counter_color(count);
+    (
+        Text2d::new(count.to_string()),
+        TextFont {
+            font: board_assets.bomb_counter_font.clone(),
+            font_size,
+            ..Default::default()
+        },
+        TextColor(color),
+        Transform::from_xyz(0., 0., 1.),
+    )
+}
+
+/// Number of stacked mines or flags, drawn over their sprite
+fn mine_count_text_bundle(
+    count: u8,
+    board_assets: &BoardAssets,
+    font_size: f32,
+) -> (Text2d, TextFont, TextColor, Transform) {
+    let (text, font, color, _) = bomb_count_text_bundle(count, board_assets, font_size);
+    (text, font, color, Transform::from_xyz(0., 0., 2.))
+}
@@ -0,0 +1,68 @@
+use bevy::{log, prelude::*};
+use minesweeper_core::Action;
+
+use crate::{
+    events::{TileChordEvent, TileMarkEvent, TileTriggerEvent},
+    resources::{Board, GameClock, ReplayPlayback},
+};
+
+pub fn play_replay(
+    time: Res<Time>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut tile_trigger_ewr: EventWriter<TileTriggerEvent>,
+    mut tile_mark_ewr: EventWriter<TileMarkEvent>,
+    mut tile_chord_ewr: EventWriter<TileChordEvent>,
+) {
+    if playback.is_finished() {
+        return;
+    }
+    for timed in playback.advance(time.delta()) {
+        match timed.action {
+            Action::Reveal(c) => {
+                tile_trigger_ewr.write(TileTriggerEvent(c));
+            }
+            Action::ToggleFlag(c) => {
+                tile_mark_ewr.write(TileMarkEvent(c));
+            }
+            Action::Chord(c) => {
+                tile_chord_ewr.write(TileChordEvent(c));
+            }
+        }
+    }
+    if playback.is_finished() {
+        log::info!("Replay finished");
+    }
+}
+
+/// Claims the result of the game in its replay once it ends
+pub fn finish_replay(mut board: ResMut<Board>, clock: Res<GameClock>) {
+    let state = board.game.state();
+    if state.is_over() && board.replay.result.is_none() {
+        board.replay.finish(state, clock.elapsed());
+    }
+}
+
+/// Records every action and counts its click, whether it comes from the mouse or from a
+/// replay being played back
+pub fn record_actions(
+    mut board: ResMut<Board>,
+    clock: Res<GameClock>,
+    mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_mark_evr: EventReader<TileMarkEvent>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+) {
+    let time = clock.elapsed();
+    for event in tile_trigger_evr.read() {
+        board.clicks.reveals += 1;
+        board.replay.record(time, Action::Reveal(event.0));
+    }
+    // Same order as the handlers apply them
+    for event in tile_chord_evr.read() {
+        board.clicks.chords += 1;
+        board.replay.record(time, Action::Chord(event.0));
+    }
+    for event in tile_mark_evr.read() {
+        board.clicks.flags += 1;
+        board.replay.record(time, Action::ToggleFlag(event.0));
+    }
+}
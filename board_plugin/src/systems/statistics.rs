@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+use crate::{
+    events::{BoardCompletedEvent, BombExplosionEvent},
+    resources::{Board, Difficulty, GameClock, ReplayPlayback, Statistics},
+};
+
+pub fn record_statistics(
+    board: Res<Board>,
+    clock: Res<GameClock>,
+    playback: Option<Res<ReplayPlayback>>,
+    mut statistics: ResMut<Statistics>,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+) {
+    // Replays are games that already counted
+    if playback.is_some() {
+        board_completed_evr.clear();
+        bomb_explosion_evr.clear();
+        return;
+    }
+    let difficulty = Difficulty::of(&board.game);
+    for _ in board_completed_evr.read() {
+        statistics.record_win(difficulty, clock.elapsed());
+    }
+    for _ in bomb_explosion_evr.read() {
+        statistics.record_loss(difficulty);
+    }
+}
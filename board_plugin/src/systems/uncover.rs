@@ -1,55 +1,89 @@
 use bevy::{log, prelude::*};
+use minesweeper_core::{CellChange, GameState};
 
 use crate::{
-    components::{Bomb, BombNeighbor, Coordinate, Uncover},
-    events::{BoardCompletedEvent, BombExplosionEvent, TileTriggerEvent},
-    resources::Board,
+    events::{
+        BoardCompletedEvent, BombExplosionEvent, TileChangeEvent, TileChordEvent, TileTriggerEvent,
+    },
+    resources::{Board, GameClock},
 };
 
 pub fn trigger_event_handler(
-    mut commands: Commands,
-    board: Res<Board>,
+    mut board: ResMut<Board>,
+    clock: Res<GameClock>,
     mut tile_trigger_evr: EventReader<TileTriggerEvent>,
+    mut tile_change_ewr: EventWriter<TileChangeEvent>,
+    mut board_completed_ewr: EventWriter<BoardCompletedEvent>,
+    mut bomb_explosion_ewr: EventWriter<BombExplosionEvent>,
 ) {
     for trigger_event in tile_trigger_evr.read() {
-        if let Some(entity) = board.tile_to_uncover(&trigger_event.0) {
-            commands.entity(*entity).insert(Uncover);
+        let generated = board.game.is_generated();
+        let changes = board.game.reveal(trigger_event.0);
+        if !generated && board.game.is_generated() {
+            board.three_bv = board.game.tile_map().three_bv();
+            #[cfg(feature = "debug")]
+            log::info!("{}", board.game.tile_map().console_output());
+            if board.game.no_guess_failed() {
+                log::warn!("Found no board solvable without guessing, playing a random one");
+            }
         }
+        publish_changes(
+            &mut board,
+            &clock,
+            changes,
+            &mut tile_change_ewr,
+            &mut board_completed_ewr,
+            &mut bomb_explosion_ewr,
+        );
     }
 }
 
-pub fn uncover_tiles(
-    mut commands: Commands,
+pub fn chord_event_handler(
     mut board: ResMut<Board>,
-    children: Query<(Entity, &ChildOf), With<Uncover>>,
-    parents: Query<(&Coordinate, Option<&Bomb>, Option<&BombNeighbor>)>,
-    mut board_completed_event_wr: EventWriter<BoardCompletedEvent>,
-    mut bomb_explossion_event_wr: EventWriter<BombExplosionEvent>,
+    clock: Res<GameClock>,
+    mut tile_chord_evr: EventReader<TileChordEvent>,
+    mut tile_change_ewr: EventWriter<TileChangeEvent>,
+    mut board_completed_ewr: EventWriter<BoardCompletedEvent>,
+    mut bomb_explosion_ewr: EventWriter<BombExplosionEvent>,
 ) {
-    for (entity, parent) in children.iter() {
-        commands.entity(entity).despawn();
-        let (coord, bomb, bomb_counter) = match parents.get(parent.0) {
-            Ok(v) => v,
-            Err(e) => {
-                log::error!("{}", e);
-                continue;
-            }
-        };
-        match board.try_uncover_tile(coord) {
-            Some(e) => log::debug!("Uncovered tile {} (entity: {:?})", coord, e),
-            None => log::debug!("Tried to uncover an already uncovered tile"),
-        }
-        if board.is_completed() {
-            log::info!("Board completed");
-            board_completed_event_wr.write(BoardCompletedEvent);
-        }
-        if bomb.is_some() {
+    for chord_event in tile_chord_evr.read() {
+        let changes = board.game.chord(chord_event.0);
+        publish_changes(
+            &mut board,
+            &clock,
+            changes,
+            &mut tile_change_ewr,
+            &mut board_completed_ewr,
+            &mut bomb_explosion_ewr,
+        );
+    }
+}
+
+fn publish_changes(
+    board: &mut Board,
+    clock: &GameClock,
+    changes: Vec<CellChange>,
+    tile_change_ewr: &mut EventWriter<TileChangeEvent>,
+    board_completed_ewr: &mut EventWriter<BoardCompletedEvent>,
+    bomb_explosion_ewr: &mut EventWriter<BombExplosionEvent>,
+) {
+    if changes.is_empty() {
+        return;
+    }
+    board.effective_clicks += 1;
+    for change in changes {
+        if let CellChange::Exploded(coordinate) = change {
             log::info!("Boom !");
-            bomb_explossion_event_wr.write(BombExplosionEvent);
-        } else if bomb_counter.is_none() {
-            for entity in board.adjacent_covered_tiles(*coord) {
-                commands.entity(entity).insert(Uncover);
-            }
+            bomb_explosion_ewr.write(BombExplosionEvent(coordinate));
         }
+        tile_change_ewr.write(TileChangeEvent(change));
+    }
+    if board.game.state() == GameState::Won {
+        let metrics = board.metrics(clock.elapsed());
+        log::info!("Board completed: {}", metrics);
+        board_completed_ewr.write(BoardCompletedEvent {
+            hints_used: board.hints_used,
+            metrics,
+        });
     }
 }
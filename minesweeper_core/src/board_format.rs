@@ -0,0 +1,417 @@
+//! Text and MBF representations of boards.
+//!
+//! The text format has one line per row, written top to bottom as the board is shown, so the
+//! first line is the highest `y`. Blank lines and lines starting with `#` are ignored.
+//!
+//! | Char      | Tile                                          |
+//! |-----------|-----------------------------------------------|
+//! | `.`       | covered safe tile                             |
+//! | `*`       | covered mine                                  |
+//! | `f`       | flag on a safe tile                           |
+//! | `F`       | flag on a mine                                |
+//! | `0` - `8` | revealed safe tile, checked against the mines |
+//! | `X`       | revealed mine, the game is lost               |
+//!
+//! A `TileMap` only reads the mines back, a `Game` also keeps the revealed and flagged tiles.
+//! Boards are read on a bounded square grid with the 8 surrounding tiles as neighbours, so a
+//! `Game` is only written with those rules, where numbers never go above 8. Both formats hold
+//! a single mine per tile, writing fails on stacked mines and on endless boards.
+//!
+//! MBF (Minesweeper Board Format) is the fixed-layout binary format shared by other clones:
+//! width and height as one byte each, the mine count as a big-endian `u16`, then one `(x, y)`
+//! byte pair per mine, with `y` counted from the top row.
+
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use crate::{Coordinate, Game, Grid, NeighborhoodRule, Tile, TileMap, Topology};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardFormatError {
+    /// No rows, or rows without tiles
+    Empty,
+    /// More rows or columns than a coordinate can hold
+    TooLarge,
+    /// A row whose length differs from the first one
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidChar {
+        line: usize,
+        column: usize,
+        found: char,
+    },
+    /// A revealed number that does not match the surrounding mines
+    WrongNumber {
+        coordinate: Coordinate,
+        expected: u8,
+        found: u8,
+    },
+    /// MBF data shorter or longer than its header announces
+    Truncated,
+    /// An MBF mine outside of the board, or listed twice
+    InvalidMine(Coordinate),
+    /// A tile holding several mines, which neither format can write
+    StackedMines,
+    /// A game on other rules than the bounded square grid with 8 neighbours, its numbers
+    /// would not be read back
+    UnsupportedRules,
+    /// An endless board, too large to be written
+    Endless,
+}
+
+/// A tile as written in the text format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    bomb: bool,
+    revealed: bool,
+    flagged: bool,
+    number: Option<u8>,
+}
+
+impl Display for BoardFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "board has no tiles"),
+            Self::TooLarge => write!(f, "board is too large"),
+            Self::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(f, "line {line}: expected {expected} tiles, found {found}"),
+            Self::InvalidChar {
+                line,
+                column,
+                found,
+            } => write!(f, "line {line}, column {column}: invalid tile '{found}'"),
+            Self::WrongNumber {
+                coordinate,
+                expected,
+                found,
+            } => write!(f, "tile {coordinate}: expected {expected}, found {found}"),
+            Self::Truncated => write!(f, "MBF data does not match its header"),
+            Self::InvalidMine(coordinate) => write!(f, "invalid MBF mine at {coordinate}"),
+            Self::StackedMines => write!(f, "board has tiles with several mines"),
+            Self::UnsupportedRules => write!(f, "board rules cannot be written"),
+            Self::Endless => write!(f, "endless boards cannot be written"),
+        }
+    }
+}
+
+impl Error for BoardFormatError {}
+
+impl Cell {
+    fn parse(c: char) -> Option<Self> {
+        let cell = Self {
+            bomb: false,
+            revealed: false,
+            flagged: false,
+            number: None,
+        };
+        Some(match c {
+            '.' => cell,
+            '*' => Self { bomb: true, ..cell },
+            'f' => Self {
+                flagged: true,
+                ..cell
+            },
+            'F' => Self {
+                bomb: true,
+                flagged: true,
+                ..cell
+            },
+            'X' => Self {
+                bomb: true,
+                revealed: true,
+                ..cell
+            },
+            '0'..='8' => Self {
+                revealed: true,
+                number: c.to_digit(10).map(|n| n as u8),
+                ..cell
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Parses the text format into the tile map and the cells, indexed like the map
+fn parse(s: &str) -> Result<(TileMap, Vec<Vec<Cell>>), BoardFormatError> {
+    let mut rows: Vec<Vec<Cell>> = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = line
+            .chars()
+            .enumerate()
+            .map(|(column, c)| {
+                Cell::parse(c).ok_or(BoardFormatError::InvalidChar {
+                    line: i + 1,
+                    column: column + 1,
+                    found: c,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(first) = rows.first()
+            && first.len() != row.len()
+        {
+            return Err(BoardFormatError::RaggedRow {
+                line: i + 1,
+                expected: first.len(),
+                found: row.len(),
+            });
+        }
+        rows.push(row);
+    }
+    rows.reverse();
+    let width = rows.first().map_or(0, Vec::len);
+    if width == 0 {
+        return Err(BoardFormatError::Empty);
+    }
+    let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(rows.len())) else {
+        return Err(BoardFormatError::TooLarge);
+    };
+    let bombs: Vec<Coordinate> = coordinates(width, height)
+        .filter(|c| rows[c.y as usize][c.x as usize].bomb)
+        .collect();
+    let tile_map = TileMap::with_bombs(width, height, &bombs);
+    for coordinate in coordinates(width, height) {
+        let Some(found) = rows[coordinate.y as usize][coordinate.x as usize].number else {
+            continue;
+        };
+        let expected = tile_map.bomb_count_at(coordinate);
+        if expected != found {
+            return Err(BoardFormatError::WrongNumber {
+                coordinate,
+                expected,
+                found,
+            });
+        }
+    }
+    Ok((tile_map, rows))
+}
+
+fn coordinates(width: u16, height: u16) -> impl Iterator<Item = Coordinate> {
+    (0..height).flat_map(move |y| (0..width).map(move |x| Coordinate { x, y }))
+}
+
+/// Whether a tile of the map holds more than one mine
+fn has_stacked_mines(tile_map: &TileMap) -> bool {
+    tile_map.coordinates().any(|c| tile_map.mines_at(c) > 1)
+}
+
+/// Writes the rows top to bottom, `char_at` picking the character of each tile
+fn write_rows(
+    tile_map: &TileMap,
+    char_at: impl Fn(Coordinate) -> char,
+) -> Result<String, BoardFormatError> {
+    if tile_map.is_endless() {
+        return Err(BoardFormatError::Endless);
+    }
+    if has_stacked_mines(tile_map) {
+        return Err(BoardFormatError::StackedMines);
+    }
+    let mut text = String::new();
+    for y in (0..tile_map.height()).rev() {
+        text.extend((0..tile_map.width()).map(|x| char_at(Coordinate { x, y })));
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+impl FromStr for TileMap {
+    type Err = BoardFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s).map(|(tile_map, _)| tile_map)
+    }
+}
+
+impl FromStr for Game {
+    type Err = BoardFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (tile_map, rows) = parse(s)?;
+        let cells: Vec<(Coordinate, Cell)> = coordinates(tile_map.width(), tile_map.height())
+            .map(|c| (c, rows[c.y as usize][c.x as usize]))
+            .collect();
+        let revealed: Vec<Coordinate> = cells
+            .iter()
+            .filter(|(_, cell)| cell.revealed)
+            .map(|&(c, _)| c)
+            .collect();
+        let flagged = cells
+            .iter()
+            .filter(|(_, cell)| cell.flagged)
+            .map(|&(c, _)| c)
+            .collect();
+        let mut game = Game::from_tile_map(tile_map);
+        game.restore(&revealed, flagged);
+        Ok(game)
+    }
+}
+
+impl Game {
+    /// Writes the game in the text format, only on the rules boards are read with
+    pub fn to_text(&self) -> Result<String, BoardFormatError> {
+        let tile_map = self.tile_map();
+        if tile_map.is_endless() {
+            return Err(BoardFormatError::Endless);
+        }
+        if tile_map.topology() != Topology::Bounded
+            || tile_map.grid() != Grid::Square
+            || tile_map.neighborhood() != NeighborhoodRule::Moore
+        {
+            return Err(BoardFormatError::UnsupportedRules);
+        }
+        write_rows(tile_map, |c| {
+            match (self.is_covered(c), self.is_flagged(c), tile_map.tile_at(c)) {
+                (_, true, Some(Tile::Bomb(_))) => 'F',
+                (_, true, _) => 'f',
+                (true, _, Some(Tile::Bomb(_))) => '*',
+                (true, _, _) => '.',
+                (false, _, Some(Tile::Bomb(_))) => 'X',
+                (false, _, Some(Tile::BombNeighbor(n))) => char::from(b'0' + n),
+                (false, _, _) => '0',
+            }
+        })
+    }
+}
+
+impl TileMap {
+    /// Writes the mines in the text format
+    pub fn to_text(&self) -> Result<String, BoardFormatError> {
+        write_rows(self, |c| if self.is_bomb_at(c) { '*' } else { '.' })
+    }
+
+    /// Reads an MBF board
+    pub fn from_mbf(bytes: &[u8]) -> Result<Self, BoardFormatError> {
+        let [width, height, count_high, count_low, mines @ ..] = bytes else {
+            return Err(BoardFormatError::Truncated);
+        };
+        let (width, height) = (*width as u16, *height as u16);
+        if width == 0 || height == 0 {
+            return Err(BoardFormatError::Empty);
+        }
+        let count = u16::from_be_bytes([*count_high, *count_low]) as usize;
+        if mines.len() != count * 2 {
+            return Err(BoardFormatError::Truncated);
+        }
+        let mut bombs: Vec<Coordinate> = Vec::with_capacity(count);
+        for pair in mines.chunks_exact(2) {
+            let (x, y) = (pair[0] as u16, pair[1] as u16);
+            if x >= width || y >= height {
+                return Err(BoardFormatError::InvalidMine(Coordinate { x, y }));
+            }
+            let coordinate = Coordinate {
+                x,
+                y: height - 1 - y,
+            };
+            if bombs.contains(&coordinate) {
+                return Err(BoardFormatError::InvalidMine(Coordinate { x, y }));
+            }
+            bombs.push(coordinate);
+        }
+        Ok(Self::with_bombs(width, height, &bombs))
+    }
+
+    /// Writes the board as MBF, which only fits boards up to 255 tiles wide and high
+    pub fn to_mbf(&self) -> Result<Vec<u8>, BoardFormatError> {
+        if self.is_endless() {
+            return Err(BoardFormatError::Endless);
+        }
+        let (Ok(width), Ok(height)) = (u8::try_from(self.width()), u8::try_from(self.height()))
+        else {
+            return Err(BoardFormatError::TooLarge);
+        };
+        if has_stacked_mines(self) {
+            return Err(BoardFormatError::StackedMines);
+        }
+        let bombs: Vec<Coordinate> = coordinates(self.width(), self.height())
+            .filter(|&c| self.is_bomb_at(c))
+            .collect();
+        let mut bytes = vec![width, height];
+        bytes.extend((bombs.len() as u16).to_be_bytes());
+        for coordinate in bombs {
+            bytes.push(coordinate.x as u8);
+            bytes.push(height - 1 - coordinate.y as u8);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{GameOptions, GameState};
+
+    #[test]
+    fn game_text_round_trip() {
+        let text = ".*F1\n1221\n0000\n";
+        let game: Game = text.parse().unwrap();
+        assert_eq!(game.state(), GameState::Playing);
+        assert_eq!(game.tile_map().bomb_count(), 2);
+        assert_eq!(game.flagged_tiles(), [Coordinate { x: 2, y: 2 }]);
+        assert_eq!(game.to_text().unwrap(), text);
+    }
+
+    #[test]
+    fn highest_number_round_trip() {
+        let text = "***\n*8*\n***\n";
+        let game: Game = text.parse().unwrap();
+        assert_eq!(game.to_text().unwrap(), text);
+    }
+
+    #[test]
+    fn wrong_number_is_rejected() {
+        let error = "*2\n..\n".parse::<Game>().unwrap_err();
+        assert_eq!(
+            error,
+            BoardFormatError::WrongNumber {
+                coordinate: Coordinate { x: 1, y: 1 },
+                expected: 1,
+                found: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn mbf_round_trip() {
+        let tile_map: TileMap = "*..\n..*\n.*.\n".parse().unwrap();
+        let bytes = tile_map.to_mbf().unwrap();
+        assert_eq!(bytes, [3, 3, 0, 3, 1, 2, 2, 1, 0, 0]);
+        let read = TileMap::from_mbf(&bytes).unwrap();
+        assert_eq!(read.to_text(), tile_map.to_text());
+    }
+
+    #[test]
+    fn stacked_mines_are_not_written() {
+        let bombs = [Coordinate { x: 0, y: 0 }, Coordinate { x: 0, y: 0 }];
+        let tile_map = TileMap::with_bombs(2, 2, &bombs);
+        assert_eq!(tile_map.to_mbf(), Err(BoardFormatError::StackedMines));
+        assert_eq!(tile_map.to_text(), Err(BoardFormatError::StackedMines));
+    }
+
+    #[test]
+    fn other_rules_are_not_written() {
+        let game = Game::new(GameOptions {
+            topology: Topology::Toroidal,
+            ..Default::default()
+        });
+        assert_eq!(game.to_text(), Err(BoardFormatError::UnsupportedRules));
+    }
+
+    #[test]
+    fn endless_boards_are_not_written() {
+        let game = Game::new(GameOptions {
+            topology: Topology::Toroidal,
+            endless: true,
+            ..Default::default()
+        });
+        assert_eq!(game.to_text(), Err(BoardFormatError::Endless));
+        assert_eq!(game.tile_map().to_mbf(), Err(BoardFormatError::Endless));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    fmt::Display,
+    ops::{Add, Sub},
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, Ord, PartialEq, PartialOrd, Eq, Hash)]
+pub struct Coordinate {
+    pub x: u16,
+    pub y: u16,
+}
+
+/// A square of `ChunkCoordinate::SIZE` tiles a side, the unit in which a `TileMap` stores and
+/// generates its tiles
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, Ord, PartialEq, PartialOrd, Eq, Hash)]
+pub struct ChunkCoordinate {
+    pub x: u16,
+    pub y: u16,
+}
+
+impl Coordinate {
+    /// The chunk holding this tile
+    pub const fn chunk(&self) -> ChunkCoordinate {
+        ChunkCoordinate {
+            x: self.x / ChunkCoordinate::SIZE,
+            y: self.y / ChunkCoordinate::SIZE,
+        }
+    }
+
+    /// Position of this tile in its chunk, row by row
+    pub(crate) const fn chunk_index(&self) -> usize {
+        let size = ChunkCoordinate::SIZE;
+        (self.y % size) as usize * size as usize + (self.x % size) as usize
+    }
+}
+
+impl ChunkCoordinate {
+    /// Width and height of a chunk, in tiles
+    pub const SIZE: u16 = 16;
+    pub(crate) const TILES: usize = Self::SIZE as usize * Self::SIZE as usize;
+
+    /// Tiles of the chunk row by row, in the order of `Coordinate::chunk_index`
+    pub fn coordinates(self) -> impl Iterator<Item = Coordinate> {
+        let (left, bottom) = (self.x * Self::SIZE, self.y * Self::SIZE);
+        (0..Self::SIZE).flat_map(move |y| {
+            (0..Self::SIZE).map(move |x| Coordinate {
+                x: left + x,
+                y: bottom + y,
+            })
+        })
+    }
+}
+
+impl Add for Coordinate {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl Sub for Coordinate {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x.saturating_sub(rhs.x),
+            y: self.y.saturating_sub(rhs.y),
+        }
+    }
+}
+
+impl Display for Coordinate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
@@ -0,0 +1,550 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::solver::{self, BoardView, CellView};
+use crate::{Coordinate, Grid, NeighborhoodRule, Tile, TileMap, Topology};
+
+/// Layouts tried by `GenerationMode::NoGuess` before settling for the last one
+const NO_GUESS_ATTEMPTS: usize = 1000;
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Won,
+    Lost,
+}
+
+impl GameState {
+    pub const fn is_over(&self) -> bool {
+        !matches!(self, Self::Playing)
+    }
+}
+
+/// A visible change to a single cell, produced by the `Game` actions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellChange {
+    Revealed {
+        coordinate: Coordinate,
+        tile: Tile,
+    },
+    Flagged(Coordinate),
+    Unflagged(Coordinate),
+    /// The mine that ended the game
+    Exploded(Coordinate),
+    /// A flag left on a safe tile when the game was lost
+    WrongFlag(Coordinate),
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GenerationMode {
+    #[default]
+    Random,
+    /// Only layouts that can be cleared from the first reveal without guessing
+    NoGuess,
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameOptions {
+    pub width: u16,
+    pub height: u16,
+    pub bomb_count: u16,
+    /// Keeps the neighbours of the first reveal clear as well as the tile itself
+    pub safe_start: bool,
+    /// Fixed generation seed, a random one is picked when unset
+    pub seed: Option<u64>,
+    pub generation: GenerationMode,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub topology: Topology,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub grid: Grid,
+    /// Which tiles are neighbours on a square grid
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub neighborhood: NeighborhoodRule,
+    /// Most mines a tile can hold. Above 1, numbers sum the mines around them and flags count
+    /// up to it
+    #[cfg_attr(feature = "serde", serde(default = "crate::tile::single_mine"))]
+    pub max_mines_per_tile: u8,
+    /// Plays on a wrapping map of `TileMap::ENDLESS_SIZE` tiles a side, generated around the
+    /// revealed tiles, with `bomb_count` mines per chunk on average. It can only be lost
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub endless: bool,
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self {
+            width: 15,
+            height: 15,
+            bomb_count: 30,
+            safe_start: false,
+            seed: None,
+            generation: GenerationMode::Random,
+            topology: Topology::Bounded,
+            grid: Grid::Square,
+            neighborhood: NeighborhoodRule::Moore,
+            max_mines_per_tile: 1,
+            endless: false,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Game {
+    options: GameOptions,
+    seed: u64,
+    tile_map: TileMap,
+    revealed: HashSet<Coordinate>,
+    flagged: Vec<Coordinate>,
+    /// Flag counts above 1, on multi-mine boards
+    #[cfg_attr(feature = "serde", serde(default))]
+    flag_counts: HashMap<Coordinate, u8>,
+    state: GameState,
+    generated: bool,
+    /// No guess generation ran out of attempts and kept a layout that needs guessing
+    #[cfg_attr(feature = "serde", serde(default))]
+    no_guess_failed: bool,
+    /// The mine that ended the game
+    #[cfg_attr(feature = "serde", serde(default))]
+    exploded: Option<Coordinate>,
+}
+
+impl Game {
+    /// Creates a fully covered game, mines are placed on the first reveal.
+    /// The same options, seed and first reveal always yield the same layout
+    pub fn new(options: GameOptions) -> Self {
+        let seed = options.seed.unwrap_or_else(|| rand::rng().random());
+        let tile_map = match options.endless {
+            true => TileMap::endless(options.bomb_count, seed),
+            false => TileMap::empty(options.width, options.height),
+        };
+        let tile_map = tile_map
+            .with_topology(options.topology)
+            .with_grid(options.grid)
+            .with_neighborhood(options.neighborhood)
+            .with_max_mines_per_tile(options.max_mines_per_tile);
+        Self {
+            options,
+            seed,
+            tile_map,
+            revealed: HashSet::new(),
+            flagged: Vec::new(),
+            flag_counts: HashMap::new(),
+            state: GameState::Playing,
+            generated: false,
+            no_guess_failed: false,
+            exploded: None,
+        }
+    }
+
+    /// Starts a game on a fixed layout, the options are taken from the map
+    pub fn from_tile_map(tile_map: TileMap) -> Self {
+        let options = GameOptions {
+            width: tile_map.width(),
+            height: tile_map.height(),
+            bomb_count: tile_map.bomb_count(),
+            topology: tile_map.topology(),
+            grid: tile_map.grid(),
+            neighborhood: tile_map.neighborhood(),
+            max_mines_per_tile: tile_map.max_mines_per_tile(),
+            endless: tile_map.is_endless(),
+            ..Default::default()
+        };
+        Self::with_tile_map(options, tile_map)
+    }
+
+    pub(crate) fn with_tile_map(options: GameOptions, tile_map: TileMap) -> Self {
+        let mut game = Self::new(options);
+        game.tile_map = tile_map;
+        game.generated = true;
+        game
+    }
+
+    /// Sets the revealed and flagged tiles of a game read back from its text form
+    pub(crate) fn restore(&mut self, revealed: &[Coordinate], flagged: Vec<Coordinate>) {
+        self.revealed
+            .extend(revealed.iter().filter(|&&c| self.tile_map.contains(c)));
+        self.flagged = flagged;
+        if revealed.iter().any(|&c| self.tile_map.is_bomb_at(c)) {
+            self.state = GameState::Lost;
+        } else if self.is_cleared() {
+            self.state = GameState::Won;
+        }
+    }
+
+    pub fn options(&self) -> &GameOptions {
+        &self.options
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn tile_map(&self) -> &TileMap {
+        &self.tile_map
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_generated(&self) -> bool {
+        self.generated
+    }
+
+    /// Whether `GenerationMode::NoGuess` found no layout solvable without guessing, in which
+    /// case the board was kept as a random one
+    pub fn no_guess_failed(&self) -> bool {
+        self.no_guess_failed
+    }
+
+    /// The mine that ended a lost game, when it was lost by playing
+    pub fn exploded(&self) -> Option<Coordinate> {
+        self.exploded
+    }
+
+    pub fn is_covered(&self, coordinate: Coordinate) -> bool {
+        self.tile_map.contains(coordinate) && !self.revealed.contains(&coordinate)
+    }
+
+    pub fn is_flagged(&self, coordinate: Coordinate) -> bool {
+        self.flagged.contains(&coordinate)
+    }
+
+    /// Mines the player put on a tile, 0 when unflagged
+    pub fn flag_count(&self, coordinate: Coordinate) -> u8 {
+        match self.is_flagged(coordinate) {
+            true => self.flag_counts.get(&coordinate).copied().unwrap_or(1),
+            false => 0,
+        }
+    }
+
+    /// Mines flagged over the whole board
+    pub fn total_flags(&self) -> usize {
+        self.flagged
+            .iter()
+            .map(|&c| self.flag_count(c) as usize)
+            .sum()
+    }
+
+    pub fn revealed_tiles(&self) -> &HashSet<Coordinate> {
+        &self.revealed
+    }
+
+    /// Safe tiles revealed, the score of an endless game
+    pub fn cleared_tiles(&self) -> usize {
+        match self.state {
+            GameState::Lost => self
+                .revealed
+                .iter()
+                .filter(|&&c| !self.tile_map.is_bomb_at(c))
+                .count(),
+            _ => self.revealed.len(),
+        }
+    }
+
+    pub fn flagged_tiles(&self) -> &[Coordinate] {
+        &self.flagged
+    }
+
+    /// The changes that draw a tile as it is now over a covered one, to show a restored game
+    /// or tiles scrolled back into view
+    pub fn restored_changes(&self, coordinate: Coordinate) -> Vec<CellChange> {
+        let mut changes = Vec::new();
+        let tile = self.tile_map.tile_at(coordinate);
+        if let Some(tile) = tile
+            && !self.is_covered(coordinate)
+        {
+            changes.push(CellChange::Revealed { coordinate, tile });
+        }
+        if self.is_flagged(coordinate) {
+            changes.push(CellChange::Flagged(coordinate));
+            if self.state == GameState::Lost && tile.is_some_and(|tile| !tile.is_bomb()) {
+                changes.push(CellChange::WrongFlag(coordinate));
+            }
+        }
+        if self.exploded == Some(coordinate) {
+            changes.push(CellChange::Exploded(coordinate));
+        }
+        changes
+    }
+
+    /// What the player sees of a finite board, too large to build on an endless one
+    pub fn view(&self) -> BoardView {
+        let mut view = BoardView::new(
+            self.tile_map.width(),
+            self.tile_map.height(),
+            self.options.bomb_count,
+        )
+        .with_topology(self.tile_map.topology())
+        .with_grid(self.tile_map.grid())
+        .with_neighborhood(self.tile_map.neighborhood());
+        for coordinate in view.coordinates() {
+            let cell = if self.is_flagged(coordinate) {
+                CellView::Flagged
+            } else if self.is_covered(coordinate) {
+                CellView::Covered
+            } else {
+                match self.tile_map.tile_at(coordinate) {
+                    Some(tile) => CellView::Revealed(tile),
+                    None => continue,
+                }
+            };
+            view.set(coordinate, cell);
+        }
+        view
+    }
+
+    pub fn reveal(&mut self, coordinate: Coordinate) -> Vec<CellChange> {
+        let mut changes = Vec::new();
+        if self.state.is_over() || !self.is_covered(coordinate) || self.is_flagged(coordinate) {
+            return changes;
+        }
+        if !self.generated {
+            self.generate(coordinate);
+        }
+        self.uncover(coordinate, &mut changes);
+        changes
+    }
+
+    /// Flags or unflags a tile. On multi-mine boards, a flagged tile counts one more mine each
+    /// time until `max_mines_per_tile`, then it is unflagged
+    pub fn toggle_flag(&mut self, coordinate: Coordinate) -> Vec<CellChange> {
+        if self.state.is_over() || !self.is_covered(coordinate) {
+            return Vec::new();
+        }
+        let count = self.flag_count(coordinate);
+        match self.flagged.iter().position(|&c| c == coordinate) {
+            Some(_) if count < self.tile_map.max_mines_per_tile() => {
+                self.flag_counts.insert(coordinate, count + 1);
+                vec![CellChange::Flagged(coordinate)]
+            }
+            Some(pos) => {
+                self.flagged.remove(pos);
+                self.flag_counts.remove(&coordinate);
+                vec![CellChange::Unflagged(coordinate)]
+            }
+            None => {
+                self.flagged.push(coordinate);
+                vec![CellChange::Flagged(coordinate)]
+            }
+        }
+    }
+
+    /// Uncovers the unflagged neighbours of a revealed number once it has as many flags around it
+    pub fn chord(&mut self, coordinate: Coordinate) -> Vec<CellChange> {
+        let mut changes = Vec::new();
+        if self.state.is_over() || self.is_covered(coordinate) {
+            return changes;
+        }
+        let Some(Tile::BombNeighbor(count)) = self.tile_map.tile_at(coordinate) else {
+            return changes;
+        };
+        let neighbors: Vec<Coordinate> = self.tile_map.safe_square_at(coordinate).collect();
+        let flags: usize = neighbors.iter().map(|&c| self.flag_count(c) as usize).sum();
+        if flags != count as usize {
+            return changes;
+        }
+        for neighbor in neighbors {
+            if self.state.is_over() {
+                break;
+            }
+            if self.is_covered(neighbor) && !self.is_flagged(neighbor) {
+                self.uncover(neighbor, &mut changes);
+            }
+        }
+        changes
+    }
+
+    fn generate(&mut self, first: Coordinate) {
+        let mut excluded = vec![first];
+        if self.options.safe_start {
+            excluded.extend(self.tile_map.safe_square_at(first));
+        }
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let empty = self.tile_map.clone();
+        // The solver only reasons about one mine per tile, over a whole board
+        let attempts = match self.options.generation {
+            GenerationMode::NoGuess
+                if self.options.max_mines_per_tile == 1 && !self.tile_map.is_endless() =>
+            {
+                NO_GUESS_ATTEMPTS
+            }
+            _ => 1,
+        };
+        for attempt in 1..=attempts {
+            self.tile_map = empty.clone();
+            self.tile_map
+                .set_bombs(self.options.bomb_count, &excluded, &mut rng);
+            if attempts == 1 || solver::is_solvable(&self.tile_map, first) {
+                break;
+            }
+            self.no_guess_failed = attempt == attempts;
+        }
+        self.generated = true;
+    }
+
+    fn uncover(&mut self, coordinate: Coordinate, changes: &mut Vec<CellChange>) {
+        let mut stack = vec![coordinate];
+        while let Some(coordinate) = stack.pop() {
+            if !self.tile_map.contains(coordinate) || !self.revealed.insert(coordinate) {
+                continue;
+            }
+            self.tile_map.generate_chunk(coordinate.chunk());
+            if let Some(pos) = self.flagged.iter().position(|&c| c == coordinate) {
+                self.flagged.remove(pos);
+                self.flag_counts.remove(&coordinate);
+                changes.push(CellChange::Unflagged(coordinate));
+            }
+            let Some(tile) = self.tile_map.tile_at(coordinate) else {
+                continue;
+            };
+            changes.push(CellChange::Revealed { coordinate, tile });
+            match tile {
+                Tile::Bomb(_) => {
+                    self.lose(coordinate, changes);
+                    return;
+                }
+                Tile::Empty => stack.extend(
+                    self.tile_map
+                        .safe_square_at(coordinate)
+                        .filter(|c| !self.revealed.contains(c)),
+                ),
+                Tile::BombNeighbor(_) => (),
+            }
+        }
+        if self.is_cleared() {
+            self.state = GameState::Won;
+        }
+    }
+
+    /// Whether only mines are left covered, never on an endless board
+    fn is_cleared(&self) -> bool {
+        let tiles = self.tile_map.width() as usize * self.tile_map.height() as usize;
+        let covered = tiles.saturating_sub(self.revealed.len());
+        !self.tile_map.is_endless()
+            && covered <= self.tile_map.bomb_count() as usize
+            && self
+                .tile_map
+                .coordinates()
+                .all(|c| self.revealed.contains(&c) || self.tile_map.is_bomb_at(c))
+    }
+
+    fn lose(&mut self, exploded: Coordinate, changes: &mut Vec<CellChange>) {
+        self.state = GameState::Lost;
+        self.exploded = Some(exploded);
+        changes.push(CellChange::Exploded(exploded));
+        let mut covered: Vec<Coordinate> = self
+            .tile_map
+            .coordinates()
+            .filter(|c| !self.revealed.contains(c))
+            .collect();
+        covered.sort();
+        for coordinate in covered {
+            let Some(tile) = self.tile_map.tile_at(coordinate) else {
+                continue;
+            };
+            match (tile.is_bomb(), self.is_flagged(coordinate)) {
+                (true, false) => {
+                    self.revealed.insert(coordinate);
+                    changes.push(CellChange::Revealed { coordinate, tile });
+                }
+                (false, true) => changes.push(CellChange::WrongFlag(coordinate)),
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_reveal_never_hits_a_mine() {
+        let first = Coordinate { x: 2, y: 2 };
+        for seed in 0..50 {
+            let mut game = Game::new(GameOptions {
+                width: 5,
+                height: 5,
+                bomb_count: 24,
+                seed: Some(seed),
+                ..Default::default()
+            });
+            game.reveal(first);
+            assert!(!game.tile_map().is_bomb_at(first), "seed {seed}");
+            assert_eq!(game.state(), GameState::Won, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn safe_start_opens_on_an_empty_tile() {
+        let first = Coordinate { x: 2, y: 2 };
+        for seed in 0..50 {
+            let mut game = Game::new(GameOptions {
+                width: 5,
+                height: 5,
+                bomb_count: 16,
+                safe_start: true,
+                seed: Some(seed),
+                ..Default::default()
+            });
+            game.reveal(first);
+            assert_eq!(
+                game.tile_map().tile_at(first),
+                Some(Tile::Empty),
+                "seed {seed}"
+            );
+            assert_ne!(game.state(), GameState::Lost, "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn flood_fill_stops_at_numbers() {
+        let mut game: Game = "..*..\n..*..\n..*..\n".parse().unwrap();
+        game.reveal(Coordinate { x: 0, y: 0 });
+        let mut revealed: Vec<Coordinate> = game.revealed_tiles().iter().copied().collect();
+        revealed.sort();
+        let expected: Vec<Coordinate> = (0..2)
+            .flat_map(|x| (0..3).map(move |y| Coordinate { x, y }))
+            .collect();
+        assert_eq!(revealed, expected);
+        assert_eq!(game.state(), GameState::Playing);
+    }
+
+    #[test]
+    fn chord_on_a_wrong_flag_loses() {
+        let mut game: Game = "*f\n1.\n".parse().unwrap();
+        let changes = game.chord(Coordinate { x: 0, y: 0 });
+        let mine = Coordinate { x: 0, y: 1 };
+        assert_eq!(game.state(), GameState::Lost);
+        assert_eq!(game.exploded(), Some(mine));
+        assert!(changes.contains(&CellChange::Exploded(mine)));
+        assert!(changes.contains(&CellChange::WrongFlag(Coordinate { x: 1, y: 1 })));
+    }
+
+    #[test]
+    fn losing_reveals_the_mines_and_wrong_flags() {
+        let mut game: Game = "*f*F\n....\n".parse().unwrap();
+        let changes = game.reveal(Coordinate { x: 0, y: 1 });
+        let (hidden, flagged) = (Coordinate { x: 2, y: 1 }, Coordinate { x: 3, y: 1 });
+        assert_eq!(game.state(), GameState::Lost);
+        assert!(changes.contains(&CellChange::Revealed {
+            coordinate: hidden,
+            tile: Tile::Bomb(1),
+        }));
+        assert!(!game.is_covered(hidden));
+        // A correct flag stays on its mine
+        assert!(game.is_covered(flagged) && game.is_flagged(flagged));
+        assert!(changes.contains(&CellChange::WrongFlag(Coordinate { x: 1, y: 1 })));
+        assert!(!changes.contains(&CellChange::WrongFlag(flagged)));
+    }
+}
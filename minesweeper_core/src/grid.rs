@@ -0,0 +1,14 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Shape of the tiles and how they sit next to each other
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Grid {
+    /// Square tiles, whose neighbours are picked by a `NeighborhoodRule`
+    #[default]
+    Square,
+    /// Pointy-top hexagons with 6 neighbours. Rows are stored as in a square grid, with the
+    /// odd rows shifted half a tile to the right
+    Hex,
+}
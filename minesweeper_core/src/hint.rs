@@ -0,0 +1,85 @@
+use std::fmt::Display;
+
+use crate::Coordinate;
+use crate::probability;
+use crate::solver::{self, BoardView, CellView};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HintReason {
+    /// Nothing is revealed yet and the first reveal never holds a mine
+    FirstReveal,
+    /// Provably safe from the visible numbers
+    Safe,
+    /// A flag on a tile the visible numbers prove safe
+    WrongFlag,
+    /// No tile is provably safe, this is the least likely to hold a mine
+    LowestProbability(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hint {
+    pub coordinate: Coordinate,
+    pub reason: HintReason,
+}
+
+impl Display for HintReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::FirstReveal => write!(f, "The first reveal is always safe"),
+            Self::Safe => write!(f, "The numbers around it prove it is safe"),
+            Self::WrongFlag => write!(f, "The numbers around it prove this flag is wrong"),
+            Self::LowestProbability(p) => write!(
+                f,
+                "Nothing is provably safe, this tile has the lowest mine chance ({:.0}%)",
+                p * 100.
+            ),
+        }
+    }
+}
+
+/// Picks a tile the player can uncover, preferring provably safe ones. Flags are not trusted,
+/// a flag proven wrong is pointed out instead
+pub fn hint(view: &BoardView) -> Option<Hint> {
+    let started = view
+        .coordinates()
+        .any(|c| matches!(view.get(c), Some(CellView::Revealed(_))));
+    if !started {
+        return Some(Hint {
+            coordinate: Coordinate {
+                x: view.width() / 2,
+                y: view.height() / 2,
+            },
+            reason: HintReason::FirstReveal,
+        });
+    }
+    let unflagged = view.without_flags();
+    let safe = solver::deduce(&unflagged).safe;
+    if let Some(&coordinate) = safe
+        .iter()
+        .find(|&&c| view.get(c) == Some(CellView::Flagged))
+    {
+        return Some(Hint {
+            coordinate,
+            reason: HintReason::WrongFlag,
+        });
+    }
+    if let Some(&coordinate) = safe.first() {
+        return Some(Hint {
+            coordinate,
+            reason: HintReason::Safe,
+        });
+    }
+    let probabilities = probability::mine_probabilities(&unflagged)?;
+    let mut candidates: Vec<(Coordinate, f64)> = probabilities
+        .into_iter()
+        .filter(|&(c, _)| view.get(c) == Some(CellView::Covered))
+        .collect();
+    candidates.sort_by_key(|&(c, _)| c);
+    candidates
+        .into_iter()
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(coordinate, p)| Hint {
+            coordinate,
+            reason: HintReason::LowestProbability(p),
+        })
+}
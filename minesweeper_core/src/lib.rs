@@ -0,0 +1,23 @@
+pub mod board_format;
+mod coordinate;
+mod game;
+mod grid;
+pub mod hint;
+mod metrics;
+pub mod neighborhood;
+pub mod probability;
+mod replay;
+pub mod solver;
+mod tile;
+mod tile_map;
+mod topology;
+
+pub use coordinate::{ChunkCoordinate, Coordinate};
+pub use game::{CellChange, Game, GameOptions, GameState, GenerationMode};
+pub use grid::Grid;
+pub use metrics::Metrics;
+pub use neighborhood::{Neighborhood, NeighborhoodRule};
+pub use replay::{Action, ClickCounts, Replay, ReplayError, ReplayResult, TimedAction};
+pub use tile::Tile;
+pub use tile_map::TileMap;
+pub use topology::Topology;
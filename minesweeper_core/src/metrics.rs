@@ -0,0 +1,45 @@
+use std::{fmt::Display, time::Duration};
+
+use crate::ClickCounts;
+
+/// Efficiency of a cleared board, the figures players compete on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+    pub three_bv: u32,
+    pub time: Duration,
+    pub clicks: ClickCounts,
+    /// Clicks that changed the board
+    pub effective_clicks: u32,
+}
+
+impl Metrics {
+    pub fn three_bv_per_second(&self) -> f64 {
+        if self.time.is_zero() {
+            return 0.;
+        }
+        self.three_bv as f64 / self.time.as_secs_f64()
+    }
+
+    /// Index of efficiency, 3BV per click
+    pub fn ioe(&self) -> f64 {
+        self.three_bv as f64 / self.clicks.total().max(1) as f64
+    }
+
+    /// Share of the clicks that changed the board
+    pub fn correctness(&self) -> f64 {
+        self.effective_clicks as f64 / self.clicks.total().max(1) as f64
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "3BV {}, 3BV/s {:.2}, IOE {:.2}, correctness {:.0}%",
+            self.three_bv,
+            self.three_bv_per_second(),
+            self.ioe(),
+            self.correctness() * 100.
+        )
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Coordinate, Grid};
+
+/// Which tiles count as the neighbours of a tile: the ones its number counts mines in, that
+/// flood fill and chording open, and that the solver reasons about
+pub trait Neighborhood {
+    /// Offsets from `coordinate` to each of its neighbours
+    fn offsets(&self, coordinate: Coordinate) -> &'static [(i8, i8)];
+}
+
+/// The 8 surrounding tiles of classic minesweeper
+#[derive(Debug, Clone, Copy)]
+pub struct Moore;
+
+/// The 4 orthogonally adjacent tiles
+#[derive(Debug, Clone, Copy)]
+pub struct VonNeumann;
+
+/// The 8 tiles a chess knight can move to
+#[derive(Debug, Clone, Copy)]
+pub struct Knight;
+
+/// The 24 tiles of the 5x5 square around the tile
+#[derive(Debug, Clone, Copy)]
+pub struct RadiusTwo;
+
+/// The 6 adjacent tiles of a hex grid
+#[derive(Debug, Clone, Copy)]
+pub struct Hex;
+
+const MOORE_COORDINATES: [(i8, i8); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+const VON_NEUMANN_COORDINATES: [(i8, i8); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+const KNIGHT_COORDINATES: [(i8, i8); 8] = [
+    (-1, -2),
+    (1, -2),
+    (-2, -1),
+    (2, -1),
+    (-2, 1),
+    (2, 1),
+    (-1, 2),
+    (1, 2),
+];
+
+const RADIUS_TWO_COORDINATES: [(i8, i8); 24] = {
+    let mut offsets = [(0, 0); 24];
+    let mut i = 0;
+    let mut y = -2;
+    while y <= 2 {
+        let mut x = -2;
+        while x <= 2 {
+            if x != 0 || y != 0 {
+                offsets[i] = (x, y);
+                i += 1;
+            }
+            x += 1;
+        }
+        y += 1;
+    }
+    offsets
+};
+
+const HEX_EVEN_ROW_COORDINATES: [(i8, i8); 6] =
+    [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+
+const HEX_ODD_ROW_COORDINATES: [(i8, i8); 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+
+impl Neighborhood for Moore {
+    fn offsets(&self, _coordinate: Coordinate) -> &'static [(i8, i8)] {
+        &MOORE_COORDINATES
+    }
+}
+
+impl Neighborhood for VonNeumann {
+    fn offsets(&self, _coordinate: Coordinate) -> &'static [(i8, i8)] {
+        &VON_NEUMANN_COORDINATES
+    }
+}
+
+impl Neighborhood for Knight {
+    fn offsets(&self, _coordinate: Coordinate) -> &'static [(i8, i8)] {
+        &KNIGHT_COORDINATES
+    }
+}
+
+impl Neighborhood for RadiusTwo {
+    fn offsets(&self, _coordinate: Coordinate) -> &'static [(i8, i8)] {
+        &RADIUS_TWO_COORDINATES
+    }
+}
+
+impl Neighborhood for Hex {
+    /// Odd rows are shifted half a tile to the right, so the offsets depend on the row parity
+    fn offsets(&self, coordinate: Coordinate) -> &'static [(i8, i8)] {
+        if coordinate.y.is_multiple_of(2) {
+            &HEX_EVEN_ROW_COORDINATES
+        } else {
+            &HEX_ODD_ROW_COORDINATES
+        }
+    }
+}
+
+/// The neighbourhoods a square grid can be played with
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NeighborhoodRule {
+    #[default]
+    Moore,
+    VonNeumann,
+    Knight,
+    RadiusTwo,
+}
+
+impl NeighborhoodRule {
+    /// The neighbourhood of the rule on `grid`, hex grids always use their 6 adjacent tiles
+    pub fn on(&self, grid: Grid) -> &'static dyn Neighborhood {
+        match (grid, self) {
+            (Grid::Hex, _) => &Hex,
+            (Grid::Square, Self::Moore) => &Moore,
+            (Grid::Square, Self::VonNeumann) => &VonNeumann,
+            (Grid::Square, Self::Knight) => &Knight,
+            (Grid::Square, Self::RadiusTwo) => &RadiusTwo,
+        }
+    }
+}
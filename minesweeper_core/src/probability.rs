@@ -0,0 +1,143 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::Coordinate;
+use crate::solver::{self, BoardView, CellView, Enumeration, EnumerationCounts};
+
+/// Largest group of linked frontier tiles enumerated for exact probabilities
+const MAX_GROUP_CELLS: usize = 48;
+
+/// Exact mine probability of every covered, unflagged tile, with flags trusted to be mines.
+///
+/// Each independent group of frontier tiles is enumerated separately, then the groups are
+/// combined by weighting every total of frontier mines with the number of ways the remaining
+/// mines fit in the unconstrained tiles. `None` when the view is contradictory or a group is
+/// too large to enumerate.
+pub fn mine_probabilities(view: &BoardView) -> Option<HashMap<Coordinate, f64>> {
+    let deductions = solver::deduce(view);
+    let mines: HashSet<Coordinate> = deductions.mines.iter().copied().collect();
+    let safe: HashSet<Coordinate> = deductions.safe.iter().copied().collect();
+    let remaining = solver::remaining_mines(view, &mines)?;
+    let constraints = solver::constraints(view, &mines, &safe)?;
+
+    let mut groups: Vec<(Vec<Coordinate>, EnumerationCounts)> = Vec::new();
+    for group in solver::constraint_groups(&constraints) {
+        let cells = solver::group_cells(&group);
+        if cells.len() > MAX_GROUP_CELLS {
+            return None;
+        }
+        let counts = Enumeration::new(&cells, &group, remaining).run()?;
+        groups.push((cells, counts));
+    }
+    let frontier: HashSet<Coordinate> = groups
+        .iter()
+        .flat_map(|(cells, _)| cells.iter().copied())
+        .collect();
+    let sea: Vec<Coordinate> = view
+        .coordinates()
+        .filter(|&c| view.get(c) == Some(CellView::Covered))
+        .filter(|c| !mines.contains(c) && !safe.contains(c) && !frontier.contains(c))
+        .collect();
+    let sea_weights = SeaWeights::new(sea.len(), remaining);
+
+    let weights: Vec<Vec<f64>> = groups
+        .iter()
+        .map(|(_, counts)| counts.solutions.iter().map(|&s| s as f64).collect())
+        .collect();
+    let all = weights.iter().fold(vec![1.], |acc, w| convolve(&acc, w));
+    let total: f64 = all
+        .iter()
+        .enumerate()
+        .map(|(k, w)| w * sea_weights.get(k))
+        .sum();
+    if total <= 0. {
+        return None;
+    }
+
+    let mut probabilities = HashMap::new();
+    probabilities.extend(mines.iter().map(|&c| (c, 1.)));
+    probabilities.extend(safe.iter().map(|&c| (c, 0.)));
+    for (g, (cells, counts)) in groups.iter().enumerate() {
+        let others = weights
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != g)
+            .fold(vec![1.], |acc, (_, w)| convolve(&acc, w));
+        for (i, &cell) in cells.iter().enumerate() {
+            let mut weight = 0.;
+            for (k, mine_counts) in counts.mine_counts.iter().enumerate() {
+                if mine_counts[i] == 0 {
+                    continue;
+                }
+                let rest: f64 = others
+                    .iter()
+                    .enumerate()
+                    .map(|(o, w)| w * sea_weights.get(k + o))
+                    .sum();
+                weight += mine_counts[i] as f64 * rest;
+            }
+            probabilities.insert(cell, weight / total);
+        }
+    }
+    if !sea.is_empty() {
+        let sea_mines: f64 = all
+            .iter()
+            .enumerate()
+            .map(|(k, w)| w * sea_weights.get(k) * remaining.saturating_sub(k) as f64)
+            .sum();
+        let probability = sea_mines / total / sea.len() as f64;
+        probabilities.extend(sea.into_iter().map(|c| (c, probability)));
+    }
+    Some(probabilities)
+}
+
+fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+/// Ways to place the mines left outside the frontier in the unconstrained tiles, scaled
+/// down so that large boards stay within `f64` range
+struct SeaWeights {
+    weights: Vec<f64>,
+}
+
+impl SeaWeights {
+    fn new(sea: usize, remaining: usize) -> Self {
+        let ln_factorial: Vec<f64> = (0..=sea)
+            .scan(0., |acc, n| {
+                if n > 0 {
+                    *acc += (n as f64).ln();
+                }
+                Some(*acc)
+            })
+            .collect();
+        let ln_weights: Vec<Option<f64>> = (0..=remaining)
+            .map(|frontier_mines| {
+                let sea_mines = remaining - frontier_mines;
+                (sea_mines <= sea).then(|| {
+                    ln_factorial[sea] - ln_factorial[sea_mines] - ln_factorial[sea - sea_mines]
+                })
+            })
+            .collect();
+        let max = ln_weights
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f64::NEG_INFINITY, f64::max);
+        let weights = ln_weights
+            .into_iter()
+            .map(|w| w.map_or(0., |w| (w - max).exp()))
+            .collect();
+        Self { weights }
+    }
+
+    /// Weight of arrangements placing `frontier_mines` mines in the frontier
+    fn get(&self, frontier_mines: usize) -> f64 {
+        self.weights.get(frontier_mines).copied().unwrap_or(0.)
+    }
+}
@@ -0,0 +1,410 @@
+use std::{error::Error, fmt::Display, time::Duration};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{CellChange, Coordinate, Game, GameOptions, GameState, Tile};
+
+/// A player action on a single tile
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Reveal(Coordinate),
+    ToggleFlag(Coordinate),
+    Chord(Coordinate),
+}
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedAction {
+    /// Game time of the action, actions before the first reveal are at zero
+    pub time: Duration,
+    pub action: Action,
+}
+
+/// Clicks by kind, left clicks reveal and right clicks flag
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClickCounts {
+    pub reveals: u32,
+    pub flags: u32,
+    pub chords: u32,
+}
+
+/// How a recorded game ended
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayResult {
+    pub state: GameState,
+    /// Game time of the action that ended the game
+    pub time: Duration,
+    pub clicks: ClickCounts,
+}
+
+/// Every action of a game, enough to rebuild it move by move
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Options of the game, with its seed always set
+    pub options: GameOptions,
+    /// The game as it started when it was not generated from the seed, like a board file
+    pub start: Option<Game>,
+    pub actions: Vec<TimedAction>,
+    /// Claimed outcome, set once the game is over
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub result: Option<ReplayResult>,
+}
+
+/// Why a replay was rejected by `Replay::verify`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayError {
+    /// Neither a seed nor a starting board to rebuild the game from
+    MissingSeed,
+    /// A starting board that does not match the options, or that was already played
+    InvalidStart,
+    /// A starting board not generated from the seed, whose mines the player could have picked
+    FixedBoard,
+    /// The replay claims no result, or its actions do not end the game
+    Unfinished,
+    /// An action timed before the previous one
+    TimeReversed { index: usize },
+    /// An action timed while the clock could not be running yet
+    EarlyAction { index: usize },
+    OutOfBounds {
+        index: usize,
+        coordinate: Coordinate,
+    },
+    /// An action after the frame in which the game ended
+    AfterGameOver { index: usize },
+    Mismatch {
+        claimed: ReplayResult,
+        actual: ReplayResult,
+    },
+}
+
+impl Action {
+    pub fn coordinate(&self) -> Coordinate {
+        match *self {
+            Self::Reveal(c) | Self::ToggleFlag(c) | Self::Chord(c) => c,
+        }
+    }
+
+    pub fn apply(&self, game: &mut Game) -> Vec<CellChange> {
+        match *self {
+            Self::Reveal(c) => game.reveal(c),
+            Self::ToggleFlag(c) => game.toggle_flag(c),
+            Self::Chord(c) => game.chord(c),
+        }
+    }
+}
+
+impl ClickCounts {
+    pub fn of(actions: &[TimedAction]) -> Self {
+        let mut counts = Self::default();
+        for timed in actions {
+            match timed.action {
+                Action::Reveal(_) => counts.reveals += 1,
+                Action::ToggleFlag(_) => counts.flags += 1,
+                Action::Chord(_) => counts.chords += 1,
+            }
+        }
+        counts
+    }
+
+    pub fn total(&self) -> u32 {
+        self.reveals + self.flags + self.chords
+    }
+}
+
+impl Replay {
+    /// Starts an empty replay of a game that has not been played yet
+    pub fn new(game: &Game) -> Self {
+        Self {
+            options: GameOptions {
+                seed: Some(game.seed()),
+                ..game.options().clone()
+            },
+            start: game.is_generated().then(|| game.clone()),
+            actions: Vec::new(),
+            result: None,
+        }
+    }
+
+    pub fn record(&mut self, time: Duration, action: Action) {
+        self.actions.push(TimedAction { time, action });
+    }
+
+    /// Claims the outcome of the game once it is over
+    pub fn finish(&mut self, state: GameState, time: Duration) {
+        self.result = Some(ReplayResult {
+            state,
+            time,
+            clicks: ClickCounts::of(&self.actions),
+        });
+    }
+
+    /// The game as it was before the first action
+    pub fn game(&self) -> Game {
+        match &self.start {
+            Some(game) => game.clone(),
+            None => Game::new(self.options.clone()),
+        }
+    }
+
+    /// Plays the actions again from the start and checks that they are possible and end the
+    /// game with the claimed result. Only games generated from the seed are accepted
+    pub fn verify(&self) -> Result<ReplayResult, ReplayError> {
+        self.check(false)
+    }
+
+    /// Like `verify`, also accepting games started from a fixed board, like a board file
+    pub fn verify_any_board(&self) -> Result<ReplayResult, ReplayError> {
+        self.check(true)
+    }
+
+    fn check(&self, allow_fixed_board: bool) -> Result<ReplayResult, ReplayError> {
+        if self.start.is_none() && self.options.seed.is_none() {
+            return Err(ReplayError::MissingSeed);
+        }
+        if let Some(start) = &self.start {
+            if !self.is_valid_start(start) {
+                return Err(ReplayError::InvalidStart);
+            }
+            if !allow_fixed_board {
+                return Err(ReplayError::FixedBoard);
+            }
+        }
+        let claimed = self.result.ok_or(ReplayError::Unfinished)?;
+        let mut game = self.game();
+        let mut previous = Duration::ZERO;
+        let mut end = None;
+        for (index, timed) in self.actions.iter().enumerate() {
+            if timed.time < previous {
+                return Err(ReplayError::TimeReversed { index });
+            }
+            previous = timed.time;
+            if let Some(end) = end {
+                // Events of the frame that ended the game are still recorded, but do nothing
+                if timed.time != end {
+                    return Err(ReplayError::AfterGameOver { index });
+                }
+                continue;
+            }
+            if !game.is_generated() && !timed.time.is_zero() {
+                return Err(ReplayError::EarlyAction { index });
+            }
+            let coordinate = timed.action.coordinate();
+            if !game.tile_map().contains(coordinate) {
+                return Err(ReplayError::OutOfBounds { index, coordinate });
+            }
+            timed.action.apply(&mut game);
+            if game.state().is_over() {
+                end = Some(timed.time);
+            }
+        }
+        let time = end.ok_or(ReplayError::Unfinished)?;
+        let actual = ReplayResult {
+            state: game.state(),
+            time,
+            clicks: ClickCounts::of(&self.actions),
+        };
+        if actual != claimed {
+            return Err(ReplayError::Mismatch { claimed, actual });
+        }
+        Ok(actual)
+    }
+
+    /// Whether `start` is an untouched board of the size, mines and rules of the options, with
+    /// the numbers its mines give
+    fn is_valid_start(&self, start: &Game) -> bool {
+        let options = GameOptions {
+            seed: self.options.seed,
+            ..start.options().clone()
+        };
+        let tile_map = start.tile_map();
+        let (width, height) = (tile_map.width(), tile_map.height());
+        // Starting boards are fixed layouts, an endless one would be too large to check
+        if options != self.options || options.endless || tile_map.is_endless() {
+            return false;
+        }
+        let coordinates: Vec<Coordinate> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Coordinate { x, y }))
+            .collect();
+        let mines: usize = coordinates
+            .iter()
+            .map(|&c| tile_map.mines_at(c) as usize)
+            .sum();
+        let numbers_match = coordinates.iter().all(|&c| match tile_map.tile_at(c) {
+            Some(Tile::Bomb(count)) => count <= tile_map.max_mines_per_tile(),
+            Some(Tile::BombNeighbor(n)) => n == tile_map.bomb_count_at(c),
+            Some(Tile::Empty) => tile_map.bomb_count_at(c) == 0,
+            None => false,
+        });
+        width == options.width
+            && height == options.height
+            && tile_map.bomb_count() == options.bomb_count
+            && mines == options.bomb_count as usize
+            && tile_map.topology() == options.topology
+            && tile_map.grid() == options.grid
+            && tile_map.neighborhood() == options.neighborhood
+            && tile_map.max_mines_per_tile() == options.max_mines_per_tile
+            && numbers_match
+            && start.is_generated()
+            && start.state() == GameState::Playing
+            && start.revealed_tiles().is_empty()
+            && start.flagged_tiles().is_empty()
+    }
+}
+
+impl Display for ReplayResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} in {:.3}s with {} reveals, {} flags and {} chords",
+            self.state,
+            self.time.as_secs_f64(),
+            self.clicks.reveals,
+            self.clicks.flags,
+            self.clicks.chords
+        )
+    }
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSeed => write!(f, "replay has no seed to rebuild the board from"),
+            Self::InvalidStart => {
+                write!(
+                    f,
+                    "replay starts from a board that does not match its options"
+                )
+            }
+            Self::FixedBoard => write!(f, "replay starts from a board not generated by the game"),
+            Self::Unfinished => write!(f, "replay does not end the game"),
+            Self::TimeReversed { index } => write!(f, "action {index} goes back in time"),
+            Self::EarlyAction { index } => {
+                write!(f, "action {index} is timed before the game started")
+            }
+            Self::OutOfBounds { index, coordinate } => {
+                write!(f, "action {index} at {coordinate} is outside of the board")
+            }
+            Self::AfterGameOver { index } => write!(f, "action {index} is after the game ended"),
+            Self::Mismatch { claimed, actual } => {
+                write!(f, "claimed \"{claimed}\" but the game ended \"{actual}\"")
+            }
+        }
+    }
+}
+
+impl Error for ReplayError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn won_fixed_replay() -> Replay {
+        let game: Game = "*.\n..\n".parse().unwrap();
+        let mut replay = Replay::new(&game);
+        for (secs, (x, y)) in [(1, (1, 1)), (2, (0, 0)), (3, (1, 0))] {
+            replay.record(
+                Duration::from_secs(secs),
+                Action::Reveal(Coordinate { x, y }),
+            );
+        }
+        replay.finish(GameState::Won, Duration::from_secs(3));
+        replay
+    }
+
+    /// A game generated from its seed, lost on the second click
+    fn lost_replay() -> Replay {
+        let first = Coordinate { x: 4, y: 4 };
+        let mut game = Game::new(GameOptions {
+            width: 9,
+            height: 9,
+            bomb_count: 10,
+            safe_start: true,
+            seed: Some(7),
+            ..Default::default()
+        });
+        let mut replay = Replay::new(&game);
+        game.reveal(first);
+        let mine = game
+            .tile_map()
+            .coordinates()
+            .find(|&c| game.tile_map().is_bomb_at(c))
+            .unwrap();
+        replay.record(Duration::ZERO, Action::Reveal(first));
+        replay.record(Duration::from_secs(2), Action::Reveal(mine));
+        replay.finish(GameState::Lost, Duration::from_secs(2));
+        replay
+    }
+
+    #[test]
+    fn genuine_replay_verifies() {
+        let result = lost_replay().verify().unwrap();
+        assert_eq!(result.state, GameState::Lost);
+        assert_eq!(result.clicks.reveals, 2);
+    }
+
+    #[test]
+    fn fixed_board_is_only_verified_on_request() {
+        let replay = won_fixed_replay();
+        assert_eq!(replay.verify(), Err(ReplayError::FixedBoard));
+        let result = replay.verify_any_board().unwrap();
+        assert_eq!(result.state, GameState::Won);
+        assert_eq!(result.clicks.reveals, 3);
+    }
+
+    #[test]
+    fn hand_picked_layout_is_rejected() {
+        // Every mine packed along the top edge, won in a single click
+        let game: Game = "*********\n*........\n.........\n.........\n.........\n\
+                          .........\n.........\n.........\n.........\n"
+            .parse()
+            .unwrap();
+        let mut replay = Replay::new(&game);
+        replay.options = GameOptions {
+            width: 9,
+            height: 9,
+            bomb_count: 10,
+            seed: replay.options.seed,
+            ..Default::default()
+        };
+        replay.record(
+            Duration::from_secs(1),
+            Action::Reveal(Coordinate { x: 8, y: 0 }),
+        );
+        replay.finish(GameState::Won, Duration::from_secs(1));
+        assert_eq!(replay.verify(), Err(ReplayError::FixedBoard));
+        assert_eq!(replay.verify_any_board().unwrap().state, GameState::Won);
+    }
+
+    #[test]
+    fn played_start_is_rejected() {
+        let mut replay = won_fixed_replay();
+        let mut start = replay.game();
+        start.reveal(Coordinate { x: 1, y: 1 });
+        replay.start = Some(start);
+        assert_eq!(replay.verify_any_board(), Err(ReplayError::InvalidStart));
+    }
+
+    #[test]
+    fn start_of_other_options_is_rejected() {
+        let mut replay = won_fixed_replay();
+        replay.options = GameOptions {
+            seed: replay.options.seed,
+            ..Default::default()
+        };
+        assert_eq!(replay.verify_any_board(), Err(ReplayError::InvalidStart));
+    }
+
+    #[test]
+    fn forged_result_is_rejected() {
+        let mut replay = lost_replay();
+        if let Some(result) = &mut replay.result {
+            result.time = Duration::from_secs(1);
+        }
+        assert!(matches!(replay.verify(), Err(ReplayError::Mismatch { .. })));
+    }
+}
@@ -0,0 +1,571 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    Coordinate, Game, GameOptions, GameState, Grid, NeighborhoodRule, Tile, TileMap, Topology,
+};
+
+/// Largest group of linked frontier tiles whose mine arrangements are enumerated
+const MAX_ENUMERATION_CELLS: usize = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CellView {
+    Covered,
+    Flagged,
+    Revealed(Tile),
+}
+
+/// What a player can see of a board
+#[derive(Debug, Clone)]
+pub struct BoardView {
+    width: u16,
+    height: u16,
+    bomb_count: u16,
+    cells: Vec<CellView>,
+    topology: Topology,
+    grid: Grid,
+    neighborhood: NeighborhoodRule,
+}
+
+impl BoardView {
+    pub fn new(width: u16, height: u16, bomb_count: u16) -> Self {
+        Self {
+            width,
+            height,
+            bomb_count,
+            cells: vec![CellView::Covered; width as usize * height as usize],
+            topology: Topology::Bounded,
+            grid: Grid::Square,
+            neighborhood: NeighborhoodRule::Moore,
+        }
+    }
+
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    pub fn with_grid(mut self, grid: Grid) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: NeighborhoodRule) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    pub fn get(&self, coordinate: Coordinate) -> Option<CellView> {
+        self.index(coordinate).map(|i| self.cells[i])
+    }
+
+    pub fn set(&mut self, coordinate: Coordinate, cell: CellView) {
+        if let Some(i) = self.index(coordinate) {
+            self.cells[i] = cell;
+        }
+    }
+
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + use<> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Coordinate { x, y }))
+    }
+
+    pub fn neighbors(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        self.topology.neighbors(
+            coordinate,
+            self.neighborhood.on(self.grid).offsets(coordinate),
+            self.width,
+            self.height,
+        )
+    }
+
+    /// The same view with every flag lifted, for reasoning that must not trust the player
+    pub fn without_flags(&self) -> Self {
+        let mut view = self.clone();
+        for cell in view.cells.iter_mut() {
+            if *cell == CellView::Flagged {
+                *cell = CellView::Covered;
+            }
+        }
+        view
+    }
+
+    fn index(&self, coordinate: Coordinate) -> Option<usize> {
+        if coordinate.x >= self.width || coordinate.y >= self.height {
+            return None;
+        }
+        Some(coordinate.y as usize * self.width as usize + coordinate.x as usize)
+    }
+}
+
+/// Tiles that are provably safe or provably mined
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Deductions {
+    pub safe: Vec<Coordinate>,
+    pub mines: Vec<Coordinate>,
+}
+
+impl Deductions {
+    pub fn is_empty(&self) -> bool {
+        self.safe.is_empty() && self.mines.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Constraint {
+    pub(crate) cells: BTreeSet<Coordinate>,
+    pub(crate) mines: usize,
+}
+
+/// Deduces what it can from the visible numbers, flags are trusted to be mines.
+///
+/// Applies, until nothing new is found: single number rules, subset rules between
+/// overlapping numbers, enumeration of every mine arrangement of small independent
+/// frontier groups, and finally the total mine count.
+pub fn deduce(view: &BoardView) -> Deductions {
+    let mut mines = HashSet::new();
+    let mut safe = HashSet::new();
+    while let Some(constraints) = constraints(view, &mines, &safe) {
+        let found = trivial_deductions(&constraints)
+            .or_else(|| subset_deductions(&constraints))
+            .or_else(|| enumeration_deductions(view, &constraints, &mines))
+            .or_else(|| global_deductions(view, &constraints, &mines, &safe));
+        let Some((new_safe, new_mines)) = found else {
+            break;
+        };
+        safe.extend(new_safe);
+        mines.extend(new_mines);
+    }
+    let mut deductions = Deductions {
+        safe: safe.into_iter().collect(),
+        mines: mines.into_iter().collect(),
+    };
+    deductions.safe.sort();
+    deductions.mines.sort();
+    deductions
+}
+
+/// Plays `tile_map` from `first` using only deductions, succeeding if it never needs to guess
+pub fn is_solvable(tile_map: &TileMap, first: Coordinate) -> bool {
+    let options = GameOptions {
+        width: tile_map.width(),
+        height: tile_map.height(),
+        bomb_count: tile_map.bomb_count(),
+        topology: tile_map.topology(),
+        grid: tile_map.grid(),
+        neighborhood: tile_map.neighborhood(),
+        max_mines_per_tile: tile_map.max_mines_per_tile(),
+        ..Default::default()
+    };
+    let mut game = Game::with_tile_map(options, tile_map.clone());
+    let mut to_reveal = vec![first];
+    loop {
+        for coordinate in to_reveal.drain(..) {
+            game.reveal(coordinate);
+        }
+        match game.state() {
+            GameState::Won => return true,
+            GameState::Lost => return false,
+            GameState::Playing => (),
+        }
+        let deductions = deduce(&game.view());
+        if deductions.is_empty() {
+            return false;
+        }
+        for mine in deductions.mines {
+            game.toggle_flag(mine);
+        }
+        to_reveal = deductions.safe;
+    }
+}
+
+type Found = (Vec<Coordinate>, Vec<Coordinate>);
+
+/// Builds one constraint per revealed number, `None` if the view is contradictory
+pub(crate) fn constraints(
+    view: &BoardView,
+    mines: &HashSet<Coordinate>,
+    safe: &HashSet<Coordinate>,
+) -> Option<Vec<Constraint>> {
+    let mut constraints = Vec::new();
+    for coordinate in view.coordinates() {
+        let count = match view.get(coordinate) {
+            Some(CellView::Revealed(Tile::BombNeighbor(v))) => v as usize,
+            Some(CellView::Revealed(Tile::Empty)) => 0,
+            _ => continue,
+        };
+        let mut cells = BTreeSet::new();
+        let mut known = 0;
+        for neighbor in view.neighbors(coordinate) {
+            match view.get(neighbor) {
+                Some(CellView::Flagged) => known += 1,
+                Some(CellView::Covered) if mines.contains(&neighbor) => known += 1,
+                Some(CellView::Covered) if !safe.contains(&neighbor) => {
+                    cells.insert(neighbor);
+                }
+                _ => (),
+            }
+        }
+        if cells.is_empty() {
+            continue;
+        }
+        let mines = count.checked_sub(known)?;
+        if mines > cells.len() {
+            return None;
+        }
+        let constraint = Constraint { cells, mines };
+        if !constraints.contains(&constraint) {
+            constraints.push(constraint);
+        }
+    }
+    Some(constraints)
+}
+
+fn trivial_deductions(constraints: &[Constraint]) -> Option<Found> {
+    let mut safe = Vec::new();
+    let mut mines = Vec::new();
+    for constraint in constraints {
+        if constraint.mines == 0 {
+            safe.extend(constraint.cells.iter().copied());
+        } else if constraint.mines == constraint.cells.len() {
+            mines.extend(constraint.cells.iter().copied());
+        }
+    }
+    (!safe.is_empty() || !mines.is_empty()).then_some((safe, mines))
+}
+
+fn subset_deductions(constraints: &[Constraint]) -> Option<Found> {
+    let mut by_cell: HashMap<Coordinate, Vec<usize>> = HashMap::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for &cell in &constraint.cells {
+            by_cell.entry(cell).or_default().push(i);
+        }
+    }
+    let mut safe = Vec::new();
+    let mut mines = Vec::new();
+    for small in constraints {
+        let Some(first) = small.cells.first() else {
+            continue;
+        };
+        for &i in &by_cell[first] {
+            let large = &constraints[i];
+            if large == small || !small.cells.is_subset(&large.cells) {
+                continue;
+            }
+            let rest = large.cells.difference(&small.cells);
+            let Some(rest_mines) = large.mines.checked_sub(small.mines) else {
+                continue;
+            };
+            let rest_len = large.cells.len() - small.cells.len();
+            if rest_mines == 0 {
+                safe.extend(rest);
+            } else if rest_mines == rest_len {
+                mines.extend(rest);
+            }
+        }
+    }
+    (!safe.is_empty() || !mines.is_empty()).then_some((safe, mines))
+}
+
+/// Tries every mine arrangement of each independent group of frontier tiles, keeping the
+/// tiles that are mined in all or none of them
+fn enumeration_deductions(
+    view: &BoardView,
+    constraints: &[Constraint],
+    mines: &HashSet<Coordinate>,
+) -> Option<Found> {
+    let remaining = remaining_mines(view, mines)?;
+    let mut safe = Vec::new();
+    let mut found_mines = Vec::new();
+    for group in constraint_groups(constraints) {
+        let cells = group_cells(&group);
+        if cells.len() > MAX_ENUMERATION_CELLS {
+            continue;
+        }
+        let Some(counts) = Enumeration::new(&cells, &group, remaining).run() else {
+            continue;
+        };
+        let total = counts.total();
+        for (i, cell) in cells.into_iter().enumerate() {
+            match counts.mined(i) {
+                0 => safe.push(cell),
+                mined if mined == total => found_mines.push(cell),
+                _ => (),
+            }
+        }
+    }
+    (!safe.is_empty() || !found_mines.is_empty()).then_some((safe, found_mines))
+}
+
+/// The tiles of a constraint group, sorted
+pub(crate) fn group_cells(group: &[&Constraint]) -> Vec<Coordinate> {
+    group
+        .iter()
+        .flat_map(|c| c.cells.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Splits the constraints into groups that share no tiles
+pub(crate) fn constraint_groups(constraints: &[Constraint]) -> Vec<Vec<&Constraint>> {
+    let mut groups: Vec<Vec<&Constraint>> = Vec::new();
+    let mut group_cells: Vec<HashSet<Coordinate>> = Vec::new();
+    for constraint in constraints {
+        let linked: Vec<usize> = group_cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cells)| constraint.cells.iter().any(|c| cells.contains(c)))
+            .map(|(i, _)| i)
+            .collect();
+        let mut group = vec![constraint];
+        let mut cells: HashSet<Coordinate> = constraint.cells.iter().copied().collect();
+        for &i in linked.iter().rev() {
+            group.extend(groups.swap_remove(i));
+            cells.extend(group_cells.swap_remove(i));
+        }
+        groups.push(group);
+        group_cells.push(cells);
+    }
+    groups
+}
+
+pub(crate) struct EnumerationCounts {
+    /// Arrangements found, indexed by the number of mines they place
+    pub(crate) solutions: Vec<u64>,
+    /// Arrangements mining each tile, indexed by the number of mines then by tile
+    pub(crate) mine_counts: Vec<Vec<u64>>,
+}
+
+impl EnumerationCounts {
+    fn total(&self) -> u64 {
+        self.solutions.iter().sum()
+    }
+
+    fn mined(&self, cell: usize) -> u64 {
+        self.mine_counts.iter().map(|counts| counts[cell]).sum()
+    }
+}
+
+/// Backtracking search over the arrangements of one constraint group
+pub(crate) struct Enumeration {
+    constraints: Vec<(Vec<usize>, usize)>,
+    by_cell: Vec<Vec<usize>>,
+    assignment: Vec<Option<bool>>,
+    max_mines: usize,
+    counts: EnumerationCounts,
+}
+
+impl Enumeration {
+    pub(crate) fn new(cells: &[Coordinate], group: &[&Constraint], max_mines: usize) -> Self {
+        let index: HashMap<Coordinate, usize> =
+            cells.iter().enumerate().map(|(i, &c)| (c, i)).collect();
+        let constraints: Vec<(Vec<usize>, usize)> = group
+            .iter()
+            .map(|c| (c.cells.iter().map(|cell| index[cell]).collect(), c.mines))
+            .collect();
+        let mut by_cell = vec![Vec::new(); cells.len()];
+        for (i, (constraint_cells, _)) in constraints.iter().enumerate() {
+            for &cell in constraint_cells {
+                by_cell[cell].push(i);
+            }
+        }
+        Self {
+            constraints,
+            by_cell,
+            assignment: vec![None; cells.len()],
+            max_mines,
+            counts: EnumerationCounts {
+                solutions: Vec::new(),
+                mine_counts: Vec::new(),
+            },
+        }
+    }
+
+    /// `None` when no arrangement satisfies the group
+    pub(crate) fn run(mut self) -> Option<EnumerationCounts> {
+        self.search(0, 0);
+        (self.counts.total() > 0).then_some(self.counts)
+    }
+
+    fn search(&mut self, cell: usize, placed: usize) {
+        if cell == self.assignment.len() {
+            let counts = &mut self.counts;
+            if counts.solutions.len() <= placed {
+                counts.solutions.resize(placed + 1, 0);
+                counts
+                    .mine_counts
+                    .resize(placed + 1, vec![0; self.assignment.len()]);
+            }
+            counts.solutions[placed] += 1;
+            for (count, assigned) in counts.mine_counts[placed].iter_mut().zip(&self.assignment) {
+                if *assigned == Some(true) {
+                    *count += 1;
+                }
+            }
+            return;
+        }
+        for mine in [false, true] {
+            if mine && placed == self.max_mines {
+                continue;
+            }
+            self.assignment[cell] = Some(mine);
+            if self.is_consistent(cell) {
+                self.search(cell + 1, placed + mine as usize);
+            }
+        }
+        self.assignment[cell] = None;
+    }
+
+    fn is_consistent(&self, cell: usize) -> bool {
+        self.by_cell[cell].iter().all(|&i| {
+            let (cells, mines) = &self.constraints[i];
+            let mut placed = 0;
+            let mut open = 0;
+            for &c in cells {
+                match self.assignment[c] {
+                    Some(true) => placed += 1,
+                    Some(false) => (),
+                    None => open += 1,
+                }
+            }
+            placed <= *mines && placed + open >= *mines
+        })
+    }
+}
+
+pub(crate) fn remaining_mines(view: &BoardView, mines: &HashSet<Coordinate>) -> Option<usize> {
+    let flagged = view
+        .coordinates()
+        .filter(|&c| view.get(c) == Some(CellView::Flagged))
+        .count();
+    (view.bomb_count() as usize).checked_sub(flagged + mines.len())
+}
+
+/// Uses the total mine count once every remaining mine or safe tile is accounted for
+fn global_deductions(
+    view: &BoardView,
+    constraints: &[Constraint],
+    mines: &HashSet<Coordinate>,
+    safe: &HashSet<Coordinate>,
+) -> Option<Found> {
+    let unknown: Vec<Coordinate> = view
+        .coordinates()
+        .filter(|&c| view.get(c) == Some(CellView::Covered))
+        .filter(|c| !mines.contains(c) && !safe.contains(c))
+        .collect();
+    if unknown.is_empty() {
+        return None;
+    }
+    let remaining = remaining_mines(view, mines)?;
+    if remaining == 0 {
+        return Some((unknown, Vec::new()));
+    }
+    if remaining == unknown.len() {
+        return Some((Vec::new(), unknown));
+    }
+    // Disjoint constraints covering every unknown tile with exactly the remaining mines
+    let mut covered = HashSet::new();
+    let mut required = 0;
+    for constraint in constraints {
+        if constraint.cells.iter().any(|c| covered.contains(c)) {
+            continue;
+        }
+        covered.extend(constraint.cells.iter().copied());
+        required += constraint.mines;
+    }
+    if required == remaining {
+        let rest: Vec<Coordinate> = unknown
+            .into_iter()
+            .filter(|c| !covered.contains(c))
+            .collect();
+        if !rest.is_empty() {
+            return Some((rest, Vec::new()));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deductions_of(text: &str) -> Deductions {
+        let game: Game = text.parse().unwrap();
+        deduce(&game.view())
+    }
+
+    #[test]
+    fn one_one_one_finds_the_middle_mine() {
+        let deductions = deductions_of(".*.\n111\n");
+        assert_eq!(
+            deductions.safe,
+            [Coordinate { x: 0, y: 1 }, Coordinate { x: 2, y: 1 }]
+        );
+        assert_eq!(deductions.mines, [Coordinate { x: 1, y: 1 }]);
+    }
+
+    #[test]
+    fn ambiguous_position_deduces_nothing() {
+        assert!(deductions_of(".*\n1.\n").is_empty());
+    }
+
+    #[test]
+    fn mine_count_clears_tiles_away_from_the_number() {
+        // The only mine is next to the 1, so every tile out of its reach is safe
+        let deductions = deductions_of(".*..\n1...\n");
+        assert_eq!(
+            deductions.safe,
+            [
+                Coordinate { x: 2, y: 0 },
+                Coordinate { x: 2, y: 1 },
+                Coordinate { x: 3, y: 0 },
+                Coordinate { x: 3, y: 1 },
+            ]
+        );
+        assert!(deductions.mines.is_empty());
+    }
+
+    #[test]
+    fn deductions_are_sound_on_generated_boards() {
+        for seed in 0..20 {
+            let mut game = Game::new(GameOptions {
+                width: 9,
+                height: 9,
+                bomb_count: 10,
+                safe_start: true,
+                seed: Some(seed),
+                ..Default::default()
+            });
+            game.reveal(Coordinate { x: 4, y: 4 });
+            while game.state() == GameState::Playing {
+                let deductions = deduce(&game.view());
+                if deductions.is_empty() {
+                    break;
+                }
+                for &mine in &deductions.mines {
+                    assert!(game.tile_map().is_bomb_at(mine), "seed {seed}: {mine:?}");
+                    if game.flag_count(mine) == 0 {
+                        game.toggle_flag(mine);
+                    }
+                }
+                for &safe in &deductions.safe {
+                    assert!(!game.tile_map().is_bomb_at(safe), "seed {seed}: {safe:?}");
+                    game.reveal(safe);
+                }
+            }
+            assert_ne!(game.state(), GameState::Lost, "seed {seed}");
+        }
+    }
+}
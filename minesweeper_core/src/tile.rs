@@ -0,0 +1,42 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    /// Number of mines stacked on the tile, only above 1 on multi-mine boards
+    Bomb(u8),
+    BombNeighbor(u8),
+    Empty,
+}
+
+impl Tile {
+    /// Most mines a tile can hold, low enough for 24 neighbours to sum into a `u8`
+    pub const MAX_MINES: u8 = 9;
+
+    pub const fn is_bomb(&self) -> bool {
+        matches!(self, Self::Bomb(_))
+    }
+
+    /// Mines on the tile itself
+    pub const fn mines(&self) -> u8 {
+        match self {
+            Self::Bomb(count) => *count,
+            _ => 0,
+        }
+    }
+
+    pub fn console_output(&self) -> String {
+        match self {
+            Tile::Bomb(_) => "*".to_string(),
+            Tile::BombNeighbor(v) => v.to_string(),
+            Tile::Empty => " ".to_string(),
+        }
+    }
+}
+
+/// Serde default of the mines a tile can hold, for data written before multi-mine boards
+#[cfg(feature = "serde")]
+pub(crate) const fn single_mine() -> u8 {
+    1
+}
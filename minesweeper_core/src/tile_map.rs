@@ -0,0 +1,425 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{ChunkCoordinate, Coordinate, Grid, NeighborhoodRule, Tile, Topology};
+
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    bomb_count: u16,
+    height: u16,
+    width: u16,
+    /// Tiles by chunk, every chunk of a finite map and the generated ones of an endless map
+    chunks: HashMap<ChunkCoordinate, Vec<Tile>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    topology: Topology,
+    #[cfg_attr(feature = "serde", serde(default))]
+    grid: Grid,
+    #[cfg_attr(feature = "serde", serde(default))]
+    neighborhood: NeighborhoodRule,
+    #[cfg_attr(feature = "serde", serde(default = "crate::tile::single_mine"))]
+    max_mines_per_tile: u8,
+    #[cfg_attr(feature = "serde", serde(default))]
+    endless: Option<EndlessMines>,
+}
+
+/// Mines of an endless map, laid out a chunk at a time from the seed
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone)]
+struct EndlessMines {
+    seed: u64,
+    /// Tiles kept clear for the first reveal
+    excluded: Vec<Coordinate>,
+    /// Mines on every tile of the generated chunks and of the chunks around them
+    layouts: HashMap<ChunkCoordinate, Vec<u8>>,
+}
+
+impl TileMap {
+    /// Width and height of an endless map, which wraps around long before anyone gets there
+    pub const ENDLESS_SIZE: u16 = 4095 * ChunkCoordinate::SIZE;
+    /// Average mines per chunk of an endless map, enough to keep the openings from running on
+    pub const ENDLESS_MINES: RangeInclusive<u16> = 38..=128;
+
+    pub fn empty(width: u16, height: u16) -> Self {
+        let chunks = (0..height.div_ceil(ChunkCoordinate::SIZE))
+            .flat_map(|y| (0..width.div_ceil(ChunkCoordinate::SIZE)).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                (
+                    ChunkCoordinate { x, y },
+                    vec![Tile::Empty; ChunkCoordinate::TILES],
+                )
+            })
+            .collect();
+        Self {
+            bomb_count: 0,
+            height,
+            width,
+            chunks,
+            topology: Topology::Bounded,
+            grid: Grid::Square,
+            neighborhood: NeighborhoodRule::Moore,
+            max_mines_per_tile: 1,
+            endless: None,
+        }
+    }
+
+    /// A wrapping map of `ENDLESS_SIZE` tiles a side, whose chunks get `mines_per_chunk` mines on
+    /// average when they are first needed. The same seed always yields the same mines
+    pub fn endless(mines_per_chunk: u16, seed: u64) -> Self {
+        Self {
+            bomb_count: mines_per_chunk
+                .clamp(*Self::ENDLESS_MINES.start(), *Self::ENDLESS_MINES.end()),
+            height: Self::ENDLESS_SIZE,
+            width: Self::ENDLESS_SIZE,
+            chunks: HashMap::new(),
+            topology: Topology::Toroidal,
+            grid: Grid::Square,
+            neighborhood: NeighborhoodRule::Moore,
+            max_mines_per_tile: 1,
+            endless: Some(EndlessMines {
+                seed,
+                excluded: Vec::new(),
+                layouts: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Sets how the edges connect, before any mines are placed
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Sets the shape of the tiles, before any mines are placed
+    pub fn with_grid(mut self, grid: Grid) -> Self {
+        self.grid = grid;
+        self
+    }
+
+    /// Sets which tiles are neighbours on a square grid, before any mines are placed
+    pub fn with_neighborhood(mut self, neighborhood: NeighborhoodRule) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    /// Sets how many mines `set_bombs` may stack on a tile, up to `Tile::MAX_MINES`
+    pub fn with_max_mines_per_tile(mut self, max_mines_per_tile: u8) -> Self {
+        self.max_mines_per_tile = max_mines_per_tile.clamp(1, Tile::MAX_MINES);
+        self
+    }
+
+    /// A map with mines on the given coordinates, and the numbers around them. A coordinate
+    /// listed several times holds as many mines
+    pub fn with_bombs(width: u16, height: u16, bombs: &[Coordinate]) -> Self {
+        let mut tile_map = Self::empty(width, height);
+        for &coordinate in bombs {
+            if let Some(tile) = tile_map.tile_mut(coordinate) {
+                *tile = Tile::Bomb(tile.mines().saturating_add(1));
+            }
+        }
+        tile_map.max_mines_per_tile = tile_map
+            .chunks
+            .values()
+            .flatten()
+            .map(Tile::mines)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        tile_map.bomb_count = bombs.len() as u16;
+        tile_map.set_numbers();
+        tile_map
+    }
+
+    pub fn console_output(&self) -> String {
+        let mut buffer = format!(
+            "Map ({}, {}) with {} bombs:\n",
+            self.width, self.height, self.bomb_count
+        );
+        if self.is_endless() {
+            return buffer;
+        }
+        let line: String = "-".repeat((self.width + 2).into());
+        buffer.push_str(&line);
+        buffer.push('\n');
+        for y in (0..self.height).rev() {
+            buffer.push('|');
+            for x in 0..self.width {
+                if let Some(tile) = self.tile_at(Coordinate { x, y }) {
+                    buffer.push_str(&tile.console_output());
+                }
+            }
+            buffer.push_str("|\n");
+        }
+        buffer.push_str(&line);
+        buffer
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Mines of the map, or average mines per chunk on an endless map
+    pub fn bomb_count(&self) -> u16 {
+        self.bomb_count
+    }
+
+    /// Whether the map is endless, with its chunks generated as they are needed
+    pub fn is_endless(&self) -> bool {
+        self.endless.is_some()
+    }
+
+    pub fn max_mines_per_tile(&self) -> u8 {
+        self.max_mines_per_tile
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn grid(&self) -> Grid {
+        self.grid
+    }
+
+    pub fn neighborhood(&self) -> NeighborhoodRule {
+        self.neighborhood
+    }
+
+    /// The neighbours of a tile, following the grid, neighbourhood and topology of the map
+    pub fn safe_square_at(&self, coordinate: Coordinate) -> impl Iterator<Item = Coordinate> {
+        self.topology.neighbors(
+            coordinate,
+            self.neighborhood.on(self.grid).offsets(coordinate),
+            self.width,
+            self.height,
+        )
+    }
+
+    pub fn contains(&self, coordinate: Coordinate) -> bool {
+        coordinate.x < self.width && coordinate.y < self.height
+    }
+
+    /// The tile at `coordinate`, `None` outside of the map or in a chunk not generated yet
+    pub fn tile_at(&self, coordinate: Coordinate) -> Option<Tile> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+        let chunk = self.chunks.get(&coordinate.chunk())?;
+        chunk.get(coordinate.chunk_index()).copied()
+    }
+
+    fn tile_mut(&mut self, coordinate: Coordinate) -> Option<&mut Tile> {
+        if !self.contains(coordinate) {
+            return None;
+        }
+        let chunk = self.chunks.get_mut(&coordinate.chunk())?;
+        chunk.get_mut(coordinate.chunk_index())
+    }
+
+    /// Every tile of a finite map, or of the generated chunks of an endless map, chunk by chunk
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.chunks
+            .keys()
+            .flat_map(|chunk| chunk.coordinates())
+            .filter(|&c| self.contains(c))
+    }
+
+    /// Generates the chunk of an endless map the first time one of its tiles is needed
+    pub(crate) fn generate_chunk(&mut self, chunk: ChunkCoordinate) {
+        let max_mines_per_tile = self.max_mines_per_tile;
+        let chunk_mines = self.bomb_count;
+        let side = self.width.div_ceil(ChunkCoordinate::SIZE) as i32;
+        let Some(endless) = self.endless.as_mut() else {
+            return;
+        };
+        if self.chunks.contains_key(&chunk) {
+            return;
+        }
+        // Numbers on the edges of the chunk count the mines of the chunks around it
+        for (dx, dy) in (-1..=1).flat_map(|dx| (-1..=1).map(move |dy| (dx, dy))) {
+            let around = ChunkCoordinate {
+                x: (chunk.x as i32 + dx).rem_euclid(side) as u16,
+                y: (chunk.y as i32 + dy).rem_euclid(side) as u16,
+            };
+            if !endless.layouts.contains_key(&around) {
+                let layout = endless.layout(around, chunk_mines, max_mines_per_tile);
+                endless.layouts.insert(around, layout);
+            }
+        }
+        let tiles = chunk
+            .coordinates()
+            .map(|coordinate| match self.layout_mines_at(coordinate) {
+                0 => match self.safe_square_at(coordinate).fold(0u8, |count, c| {
+                    count.saturating_add(self.layout_mines_at(c))
+                }) {
+                    0 => Tile::Empty,
+                    count => Tile::BombNeighbor(count),
+                },
+                mines => Tile::Bomb(mines),
+            })
+            .collect();
+        self.chunks.insert(chunk, tiles);
+    }
+
+    /// Mines laid out on a tile of an endless map, generated or not
+    fn layout_mines_at(&self, coordinate: Coordinate) -> u8 {
+        self.endless
+            .as_ref()
+            .and_then(|endless| endless.layouts.get(&coordinate.chunk()))
+            .and_then(|layout| layout.get(coordinate.chunk_index()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn is_bomb_at(&self, coordinate: Coordinate) -> bool {
+        self.tile_at(coordinate).is_some_and(|tile| tile.is_bomb())
+    }
+
+    /// Mines stacked on a tile
+    pub fn mines_at(&self, coordinate: Coordinate) -> u8 {
+        self.tile_at(coordinate).map_or(0, |tile| tile.mines())
+    }
+
+    /// Mines summed over the neighbours of a safe tile
+    pub fn bomb_count_at(&self, coordinate: Coordinate) -> u8 {
+        if self.is_bomb_at(coordinate) {
+            return 0;
+        }
+        self.safe_square_at(coordinate)
+            .fold(0, |count, coord| count.saturating_add(self.mines_at(coord)))
+    }
+
+    /// Minimum number of left clicks to clear the board: one per opening, plus one per number
+    /// that does not border an opening
+    pub fn three_bv(&self) -> u32 {
+        if self.is_endless() {
+            return 0;
+        }
+        let mut opened: HashSet<Coordinate> = HashSet::new();
+        let mut count = 0;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coordinate = Coordinate { x, y };
+                if self.tile_at(coordinate) != Some(Tile::Empty) || opened.contains(&coordinate) {
+                    continue;
+                }
+                count += 1;
+                let mut stack = vec![coordinate];
+                opened.insert(coordinate);
+                while let Some(coordinate) = stack.pop() {
+                    for neighbor in self.safe_square_at(coordinate) {
+                        if !opened.insert(neighbor) {
+                            continue;
+                        }
+                        if self.tile_at(neighbor) == Some(Tile::Empty) {
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let coordinate = Coordinate { x, y };
+                if let Some(Tile::BombNeighbor(_)) = self.tile_at(coordinate)
+                    && !opened.contains(&coordinate)
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    /// Places `bomb_count` mines away from the excluded tiles, or as many as fit, stacking up
+    /// to `max_mines_per_tile` on a tile. An endless map only keeps the excluded tiles clear,
+    /// its mines are laid out with its chunks
+    pub fn set_bombs<R: Rng>(&mut self, bomb_count: u16, excluded: &[Coordinate], rng: &mut R) {
+        if let Some(endless) = self.endless.as_mut() {
+            endless.excluded = excluded.to_vec();
+            return;
+        }
+        let excluded_tiles = excluded
+            .iter()
+            .filter(|&&c| self.contains(c))
+            .collect::<HashSet<_>>()
+            .len();
+        let free_room = ((self.width as usize * self.height as usize)
+            .saturating_sub(excluded_tiles)
+            * self.max_mines_per_tile as usize)
+            .min(u16::MAX as usize) as u16;
+        let bomb_count = bomb_count.min(free_room);
+        self.bomb_count = bomb_count;
+        let mut remaining_bombs = bomb_count;
+        while remaining_bombs > 0 {
+            let (x, y) = (
+                rng.random_range(0..self.width),
+                rng.random_range(0..self.height),
+            );
+            if excluded.contains(&Coordinate { x, y }) {
+                continue;
+            }
+            let max_mines_per_tile = self.max_mines_per_tile;
+            let Some(tile) = self.tile_mut(Coordinate { x, y }) else {
+                continue;
+            };
+            match *tile {
+                Tile::Empty => *tile = Tile::Bomb(1),
+                Tile::Bomb(count) if count < max_mines_per_tile => *tile = Tile::Bomb(count + 1),
+                _ => continue,
+            }
+            remaining_bombs -= 1;
+        }
+        self.set_numbers();
+    }
+
+    fn set_numbers(&mut self) {
+        let coordinates: Vec<Coordinate> = self.coordinates().collect();
+        for coordinate in coordinates {
+            if self.is_bomb_at(coordinate) {
+                continue;
+            }
+            let num = self.bomb_count_at(coordinate);
+            if num == 0 {
+                continue;
+            }
+            if let Some(tile) = self.tile_mut(coordinate) {
+                *tile = Tile::BombNeighbor(num);
+            }
+        }
+    }
+}
+
+impl EndlessMines {
+    /// Mines on every tile of a chunk, each tile holding up to `max_mines_per_tile`
+    fn layout(&self, chunk: ChunkCoordinate, chunk_mines: u16, max_mines_per_tile: u8) -> Vec<u8> {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        rng.set_stream(((chunk.x as u64) << 16) | chunk.y as u64);
+        let chance = (chunk_mines as f64
+            / (ChunkCoordinate::TILES * max_mines_per_tile as usize) as f64)
+            .min(1.0);
+        chunk
+            .coordinates()
+            .map(|coordinate| {
+                let mines = (0..max_mines_per_tile)
+                    .filter(|_| rng.random_bool(chance))
+                    .count() as u8;
+                // Excluded tiles still draw, so that they do not shift the rest of the chunk
+                match self.excluded.contains(&coordinate) {
+                    true => 0,
+                    false => mines,
+                }
+            })
+            .collect()
+    }
+}
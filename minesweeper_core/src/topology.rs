@@ -0,0 +1,66 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Coordinate;
+
+/// How the edges of a board connect, which decides the neighbours of every tile
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Topology {
+    /// Tiles on the edges have fewer neighbours
+    #[default]
+    Bounded,
+    /// Opposite edges are joined, so every tile has the same neighbours as any other
+    Toroidal,
+}
+
+impl Topology {
+    /// Moves `coordinate` by `offset` on a `width` x `height` board, `None` if it leaves the board
+    pub fn offset(
+        &self,
+        coordinate: Coordinate,
+        (dx, dy): (i8, i8),
+        width: u16,
+        height: u16,
+    ) -> Option<Coordinate> {
+        if coordinate.x >= width || coordinate.y >= height {
+            return None;
+        }
+        let x = coordinate.x as i32 + dx as i32;
+        let y = coordinate.y as i32 + dy as i32;
+        let (width, height) = (width as i32, height as i32);
+        match self {
+            Self::Bounded => {
+                ((0..width).contains(&x) && (0..height).contains(&y)).then_some(Coordinate {
+                    x: x as u16,
+                    y: y as u16,
+                })
+            }
+            Self::Toroidal => Some(Coordinate {
+                x: x.rem_euclid(width) as u16,
+                y: y.rem_euclid(height) as u16,
+            }),
+        }
+    }
+
+    /// The tiles reached from `coordinate` by each of the `offsets`, each listed once and
+    /// never the tile itself, as offsets can wrap back onto it on small toroidal boards
+    pub fn neighbors<'a>(
+        &self,
+        coordinate: Coordinate,
+        offsets: &'a [(i8, i8)],
+        width: u16,
+        height: u16,
+    ) -> impl Iterator<Item = Coordinate> + use<'a> {
+        let topology = *self;
+        offsets.iter().enumerate().filter_map(move |(i, &offset)| {
+            let neighbor = topology.offset(coordinate, offset, width, height)?;
+            let repeated = topology == Self::Toroidal
+                && (neighbor == coordinate
+                    || offsets[..i].iter().any(|&previous| {
+                        topology.offset(coordinate, previous, width, height) == Some(neighbor)
+                    }));
+            (!repeated).then_some(neighbor)
+        })
+    }
+}
@@ -4,9 +4,23 @@ use bevy::{log, prelude::*};
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 use board_plugin::{
     BoardPlugin,
-    resources::{BoardAssets, BoardOptions, SpriteMaterial},
+    events::{BoardCompletedEvent, BombExplosionEvent, HintRequestEvent},
+    resources::{
+        Board, BoardConfigHandle, DataFileError, GameClock, HighScores, NameEntry,
+        ProbabilityOverlay, ReplayFile, ReplayPlayback, SavedGame, Statistics,
+    },
+    screens::{MenuScreenPlugin, StatsScreenPlugin},
 };
 
+const SAVE_FILE: &str = "savegame.ron";
+const REPLAY_FILE: &str = "replay.ron";
+const STATS_FILE: &str = "stats.ron";
+const HIGH_SCORES_FILE: &str = "highscores.ron";
+
+/// Seed of the game the save file holds, so only that game ending discards it
+#[derive(Debug, Clone, Copy, Resource)]
+struct SaveOwner(u64);
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, States, Default)]
 pub enum AppState {
     InGame,
@@ -14,6 +28,7 @@ pub enum AppState {
     Out,
     ReGen,
     Pause,
+    Stats,
 }
 
 fn main() {
@@ -29,8 +44,29 @@ fn main() {
         running_state: AppState::InGame,
         paused_state: AppState::Pause,
     });
+    app.add_plugins(StatsScreenPlugin {
+        state: AppState::Stats,
+    });
+    app.add_plugins(MenuScreenPlugin {
+        state: AppState::Out,
+        start_state: AppState::InGame,
+    });
     app.add_systems(Startup, camera_setup);
-    app.add_systems(Update, state_handler);
+    app.add_systems(
+        Update,
+        (state_handler, replay_handler).run_if(not(resource_exists::<NameEntry>)),
+    );
+    app.add_systems(Update, discard_finished_save);
+    app.add_systems(
+        Update,
+        save_statistics
+            .run_if(resource_changed::<Statistics>.and(not(resource_added::<Statistics>))),
+    );
+    app.add_systems(
+        Update,
+        save_high_scores
+            .run_if(resource_changed::<HighScores>.and(not(resource_added::<HighScores>))),
+    );
     app.run();
 }
 
@@ -40,9 +76,13 @@ fn camera_setup(mut commands: Commands) {
 }
 
 fn state_handler(
+    mut commands: Commands,
     keys: Res<ButtonInput<KeyCode>>,
     mut next_state: ResMut<NextState<AppState>>,
     state: Res<State<AppState>>,
+    mut overlay: ResMut<ProbabilityOverlay>,
+    mut hint_request_ewr: EventWriter<HintRequestEvent>,
+    (board, clock): (Option<Res<Board>>, Option<Res<GameClock>>),
 ) {
     if keys.just_pressed(KeyCode::KeyC) {
         log::info!("clearing game");
@@ -60,47 +100,146 @@ fn state_handler(
             next_state.set(AppState::InGame);
         }
     }
+    if keys.just_pressed(KeyCode::KeyT) {
+        match state.get() {
+            AppState::Out => next_state.set(AppState::Stats),
+            AppState::Stats => next_state.set(AppState::Out),
+            _ => (),
+        }
+    }
+    if keys.just_pressed(KeyCode::KeyP) {
+        overlay.enabled = !overlay.enabled;
+        log::info!("Probability overlay: {}", overlay.enabled);
+    }
+    if keys.just_pressed(KeyCode::KeyS)
+        && let (Some(board), Some(clock)) = (board, clock)
+    {
+        if board.game.state().is_over() {
+            log::info!("Only games in progress can be saved");
+        } else {
+            match SavedGame::capture(&board, &clock).save(SAVE_FILE) {
+                Ok(()) => {
+                    log::info!("Game saved to {}", SAVE_FILE);
+                    commands.insert_resource(SaveOwner(board.game.seed()));
+                }
+                Err(e) => log::error!("Failed to save game: {}", e),
+            }
+        }
+    }
+    if keys.just_pressed(KeyCode::KeyH) {
+        hint_request_ewr.write(HintRequestEvent);
+    }
     if let AppState::ReGen = state.get() {
         next_state.set(AppState::InGame);
     }
 }
 
+fn replay_handler(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    board: Option<Res<Board>>,
+    playback: Option<ResMut<ReplayPlayback>>,
+) {
+    if keys.just_pressed(KeyCode::KeyR)
+        && let Some(board) = board
+    {
+        match ReplayFile::capture(&board).save(REPLAY_FILE) {
+            Ok(()) => log::info!("Replay saved to {}", REPLAY_FILE),
+            Err(e) => log::error!("Failed to save replay: {}", e),
+        }
+    }
+    if keys.just_pressed(KeyCode::KeyL) {
+        match ReplayFile::load(REPLAY_FILE) {
+            Ok(replay) => {
+                log::info!("loading replay");
+                commands.insert_resource(replay);
+                next_state.set(AppState::ReGen);
+            }
+            Err(e) => log::error!("Failed to load replay: {}", e),
+        }
+    }
+    if let Some(mut playback) = playback {
+        if keys.just_pressed(KeyCode::Equal) {
+            playback.speed = (playback.speed * 2.).min(16.);
+            log::info!("Replay speed: {}x", playback.speed);
+        }
+        if keys.just_pressed(KeyCode::Minus) {
+            playback.speed = (playback.speed / 2.).max(0.25);
+            log::info!("Replay speed: {}x", playback.speed);
+        }
+    }
+}
+
+/// A save of a game that has ended would bring back a finished board. Saves of other
+/// games, and replays being played back, leave it alone
+fn discard_finished_save(
+    mut commands: Commands,
+    mut board_completed_evr: EventReader<BoardCompletedEvent>,
+    mut bomb_explosion_evr: EventReader<BombExplosionEvent>,
+    board: Option<Res<Board>>,
+    owner: Option<Res<SaveOwner>>,
+    playback: Option<Res<ReplayPlayback>>,
+) {
+    let completed = board_completed_evr.read().count() > 0;
+    let exploded = bomb_explosion_evr.read().count() > 0;
+    if !(completed || exploded) || playback.is_some() {
+        return;
+    }
+    if let (Some(board), Some(owner)) = (board, owner)
+        && board.game.seed() == owner.0
+    {
+        delete_save();
+        commands.remove_resource::<SaveOwner>();
+    }
+}
+
+fn delete_save() {
+    if let Err(e) = SavedGame::delete(SAVE_FILE) {
+        log::error!("Failed to delete saved game: {}", e);
+    }
+}
+
+fn save_statistics(statistics: Res<Statistics>) {
+    if let Err(e) = statistics.save(STATS_FILE) {
+        log::error!("Failed to save statistics: {}", e);
+    }
+}
+
+fn save_high_scores(high_scores: Res<HighScores>) {
+    if let Err(e) = high_scores.save(HIGH_SCORES_FILE) {
+        log::error!("Failed to save high scores: {}", e);
+    }
+}
+
 fn setup_board(
     mut commands: Commands,
     mut state: ResMut<NextState<AppState>>,
     asset_server: Res<AssetServer>,
 ) {
-    commands.insert_resource(BoardOptions {
-        map_size: (20, 20),
-        bomb_count: 40,
-        tile_padding: 1.,
-        safe_start: true,
-        ..Default::default()
-    });
-    commands.insert_resource(BoardAssets {
-        label: "Default".to_string(),
-        board_material: SpriteMaterial {
-            color: Color::WHITE,
-            ..Default::default()
-        },
-        tile_material: SpriteMaterial {
-            color: Color::BLACK.lighter(0.2),
-            ..Default::default()
-        },
-        covered_tile_material: SpriteMaterial {
-            color: Color::BLACK.lighter(0.5),
-            ..Default::default()
-        },
-        bomb_counter_font: asset_server.load("fonts/pixeled.ttf"),
-        bomb_counter_colors: BoardAssets::default_colors(),
-        flag_material: SpriteMaterial {
-            texture: asset_server.load("sprites/flag.png"),
-            ..Default::default()
-        },
-        bomb_material: SpriteMaterial {
-            texture: asset_server.load("sprites/bomb.png"),
-            ..Default::default()
-        },
-    });
+    // A save is restored once, then the game goes on without it until saved again
+    match SavedGame::load(SAVE_FILE) {
+        Ok(saved) => {
+            commands.insert_resource(SaveOwner(saved.game.seed()));
+            commands.insert_resource(saved);
+            delete_save();
+        }
+        Err(e @ DataFileError::Outdated { .. }) => {
+            log::warn!("Discarding saved game: {}", e);
+            delete_save();
+        }
+        Err(e) => log::info!("No saved game restored: {}", e),
+    }
+    match Statistics::load(STATS_FILE) {
+        Ok(statistics) => commands.insert_resource(statistics),
+        Err(e) => log::info!("No statistics loaded: {}", e),
+    }
+    match HighScores::load(HIGH_SCORES_FILE) {
+        Ok(high_scores) => commands.insert_resource(high_scores),
+        Err(e) => log::info!("No high scores loaded: {}", e),
+    }
+    commands.insert_resource(BoardConfigHandle(
+        asset_server.load("boards/default.board.ron"),
+    ));
     state.set(AppState::InGame);
 }
@@ -0,0 +1,54 @@
+//! Checks replay files against the game rules and the result they claim.
+//!
+//! Usage: `verify_replay [--any-board] <replay.ron>...`, exits with an error if any replay is
+//! rejected. Games started from a fixed board instead of their seed are rejected unless
+//! `--any-board` is given, their mines could have been picked by hand.
+
+use std::{env, fs, process::ExitCode};
+
+use minesweeper_core::Replay;
+use serde::Deserialize;
+
+/// The part of a replay file the rules need, its board options only matter to the game
+#[derive(Deserialize)]
+struct ReplayFile {
+    replay: Replay,
+}
+
+fn load(path: &str) -> Result<Replay, String> {
+    let data = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let file: ReplayFile = ron::from_str(&data).map_err(|e| e.to_string())?;
+    Ok(file.replay)
+}
+
+fn main() -> ExitCode {
+    let (flags, paths): (Vec<String>, Vec<String>) =
+        env::args().skip(1).partition(|arg| arg.starts_with("--"));
+    let any_board = flags.iter().any(|flag| flag == "--any-board");
+    if paths.is_empty() || flags.len() != usize::from(any_board) {
+        eprintln!("usage: verify_replay [--any-board] <replay.ron>...");
+        return ExitCode::FAILURE;
+    }
+    let mut all_verified = true;
+    for path in paths {
+        let verified = load(&path).and_then(|replay| {
+            let result = match any_board {
+                true => replay.verify_any_board(),
+                false => replay.verify(),
+            };
+            result.map_err(|e| e.to_string())
+        });
+        match verified {
+            Ok(result) => println!("{}: verified, {}", path, result),
+            Err(e) => {
+                println!("{}: rejected, {}", path, e);
+                all_verified = false;
+            }
+        }
+    }
+    if all_verified {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}